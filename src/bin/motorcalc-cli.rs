@@ -0,0 +1,21 @@
+use motorcalc::cli::{batch, identify, repl, solve, tui};
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match args.first().map(String::as_str) {
+        Some("batch") => batch::run(&args[1..]),
+        Some("identify") => identify::run(&args[1..]),
+        Some("repl") => repl::run(),
+        Some("tui") => tui::run(),
+        _ => solve::run(&args),
+    };
+
+    match result {
+        Ok(out) => print!("{}", out),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
@@ -1,167 +1,258 @@
-use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Index, IndexMut};
 
-use crate::calc::equation::Equation;
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::calc::field::Field;
+use crate::calc::newton::{self, Report};
 use crate::calc::number::Num;
-use crate::calc::operation::Op;
+use crate::calc::registry::relations;
+use crate::calc::validation::{self, Severity};
+use crate::error::Error;
+use crate::error::ErrorKind::{Overconstrained, Underconstrained};
+
+/// The relative deviation up to which both sides of a relation are considered equal.
+const TOLERANCE: f64 = 1e-9;
+/// The maximum number of solutions searched for.
+const MAX_SOLUTIONS: usize = 8;
 
 /// A struct that holds the data necessary for calculations regarding an electrical motor and it's
-/// transmission.
+/// transmission, a value for every field declared by the registry. It's serialized as a map from
+/// the ids of the fields to their values, fields missing when deserializing are set to Num::None
+/// and unknown ones are ignored.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Calculation {
-    // Motor
-    /// Voltage: U [V]
-    pub u: Num,
-    /// Current: I [A]
-    pub i: Num,
-    /// Armature resistance: R<sub>A</sub> [Ω]
-    pub r_a: Num,
-    /// Input power: P<sub>in</sub> [W]
-    pub p_in: Num,
-    /// Motor power: P<sub>M</sub> [W]
-    pub p_m: Num,
-    /// Motor power loss: P<sub>ML</sub> [W]
-    pub p_m_l: Num,
-    /// Electrical motor power loss: P<sub>ML_el</sub> [W]
-    pub p_m_l_el: Num,
-    /// Mechanical motor power loss: P<sub>ML_mech</sub> [W]
-    pub p_m_l_mech: Num,
-    /// Motor Efficiency: η<sub>M</sub> [%]
-    pub eta_m: Num,
-    /// Motor torque: M<sub>M</sub> [Nm]
-    pub m_m: Num,
-    /// Motor speed: n<sub>M</sub> [rpm]
-    pub n_m: Num,
-
-    // Transmission
-    /// Transmission ratio: i
-    pub i_t: Num,
-    /// Transmission power: P<sub>T</sub> [W]
-    pub p_t: Num,
-    /// Transmission power loss: P<sub>TL</sub> [W]
-    pub p_t_l: Num,
-    /// Transmission Efficiency: η<sub>M</sub> [%]
-    pub eta_t: Num,
-    /// Transmission torque: M<sub>T</sub> [Nm]
-    pub m_t: Num,
-    /// Transmission speed: n<sub>T</sub> [rpm]
-    pub n_t: Num,
+    values: [Num; Field::ALL.len()],
 }
 
 impl Calculation {
     pub fn new() -> Calculation {
         Calculation {
-            u: Num::None,
-            i: Num::None,
-            r_a: Num::None,
-            p_in: Num::None,
-            p_m: Num::None,
-            p_m_l: Num::None,
-            p_m_l_el: Num::None,
-            p_m_l_mech: Num::None,
-            eta_m: Num::None,
-            m_m: Num::None,
-            n_m: Num::None,
-            i_t: Num::None,
-            p_t: Num::None,
-            p_t_l: Num::None,
-            eta_t: Num::None,
-            m_t: Num::None,
-            n_t: Num::None,
+            values: [Num::None; Field::ALL.len()],
         }
     }
 
-    /// Attempts to fill the missing fields by calculating their values.
-    pub fn try_fill_missing(&self) -> crate::error::Result<Calculation> {
-        let mut calc = self.clear_output();
+    /// Returns the value of the field, the same as `self[field]`.
+    pub fn get(&self, field: Field) -> Num {
+        self[field]
+    }
+
+    /// Returns a mutable reference to the value of the field, the same as `&mut self[field]`.
+    pub fn get_mut(&mut self, field: Field) -> &mut Num {
+        &mut self[field]
+    }
+
+    /// Returns true if any field is missing, false otherwise.
+    pub fn is_incomplete(&self) -> bool {
+        Field::ALL.iter().any(|f| self.get(*f).is_none())
+    }
+
+    /// Returns the number of fields that have a value. Solving only ever fills missing fields, so
+    /// it makes progress exactly when this number grows.
+    fn filled(&self) -> usize {
+        Field::ALL.iter().filter(|f| !self.get(**f).is_none()).count()
+    }
+
+    /// Fills the missing fields that can be calculated from the others. Fields that can't be
+    /// calculated are left missing, use check to find out why.
+    pub fn fill_missing(&self) -> Calculation {
+        self.solve().0
+    }
+
+    /// Fills the missing fields by solving the relations one after another. If that stalls,
+    /// groups of relations are solved simultaneously and the report of the last simultaneous
+    /// solve is returned.
+    pub fn solve(&self) -> (Calculation, Option<Report>) {
+        let mut calc = self.clear_output().propagate();
+        let mut report = None;
 
-        for _ in 0..6 {
-            if let Ok(c) = calc.calculate() {
-                calc = c;
+        while calc.is_incomplete() {
+            let (solved, r) = newton::solve_simultaneous(&calc);
+            match r {
+                Some(r) => report = Some(r),
+                None => break,
+            }
+            if solved.filled() <= calc.filled() {
+                break;
             }
+            calc = solved.propagate();
         }
 
-        Ok(calc)
+        (calc, report)
     }
 
-    /// Attempts to calculate the missing values.
-    pub fn calculate(&self) -> crate::error::Result<Calculation> {
-        let mut calc = *self;
+    /// Returns every physically valid solution of the calculation. Groups of relations with more
+    /// than one solution, like the current of a motor delivering a given power, branch the
+    /// calculation. The solutions are ordered by the value of the first field of the group that
+    /// branched, the low current operating point comes first.
+    pub fn solutions(&self) -> Vec<Calculation> {
+        let mut open = vec![self.clear_output().propagate()];
+        let mut solutions = Vec::new();
 
-        Equation::solve_and_assign(&mut calc.u, Op::Mul, &mut calc.i, &mut calc.p_in);
+        while let Some(calc) = open.pop() {
+            let branches = if calc.is_incomplete() {
+                newton::branches(&calc)
+            } else {
+                Vec::new()
+            };
 
-        Equation::solve_and_assign(&mut calc.p_m_l, Op::Add, &mut calc.p_m, &mut calc.p_in);
+            if branches.is_empty() {
+                if calc.is_physical() && !solutions.contains(&calc) {
+                    solutions.push(calc);
+                }
+            } else if solutions.len() + open.len() < MAX_SOLUTIONS {
+                open.extend(branches.iter().rev().map(Calculation::propagate));
+            }
+        }
 
-        Equation::solve_and_assign(&mut calc.p_m_l_el, Op::Add, &mut calc.p_m_l_mech, &mut calc.p_m_l);
+        solutions
+    }
 
-        Equation::solve_and_assign(&mut calc.p_t, Op::Add, &mut calc.p_t_l, &mut calc.p_m);
+    /// Returns true if no calculated value violates a constraint with the severity Error, false
+    /// otherwise.
+    pub fn is_physical(&self) -> bool {
+        validation::validate(self)
+            .iter()
+            .all(|i| i.severity != Severity::Error || !self.get(i.field).is_output())
+    }
 
-        Equation::solve_and_assign(&mut calc.n_m, Op::Mul, &mut calc.i_t, &mut calc.n_t);
+    /// Solves the relations one after another until no more fields can be calculated.
+    fn propagate(&self) -> Calculation {
+        let mut calc = *self;
 
-        if let Ok(eq) = Equation::new(calc.i * calc.i, Op::Mul, calc.r_a, calc.p_m_l_el).solve() {
-            calc.i = eq.a.sqrt();
-            calc.r_a = eq.b;
-            calc.p_m_l_el = eq.c;
+        loop {
+            let next = calc.calculate();
+            if next.filled() <= calc.filled() {
+                return calc;
+            }
+            calc = next;
         }
+    }
 
-        if let Ok(eq) = Equation::new(calc.p_in, Op::Mul, calc.eta_m / 100.0, calc.p_m).solve() {
-            calc.p_in = eq.a;
-            calc.eta_m = eq.b * 100.0;
-            calc.p_m = eq.c;
-        }
+    /// Calculates the missing values that a relation determines from the known ones, every
+    /// relation once.
+    pub fn calculate(&self) -> Calculation {
+        let mut calc = *self;
 
-        if let Ok(eq) = Equation::new(calc.n_m * (2.0 * PI / 60.0), Op::Mul, calc.m_m, calc.p_m).solve() {
-            calc.n_m = eq.a / (2.0 * PI / 60.0);
-            calc.m_m = eq.b;
-            calc.p_m = eq.c;
+        for relation in relations().iter() {
+            relation.solve_and_assign(&mut calc);
         }
 
-        if let Ok(eq) = Equation::new(calc.p_m, Op::Mul, calc.eta_t / 100.0, calc.p_t).solve() {
-            calc.p_m = eq.a;
-            calc.eta_t = eq.b * 100.0;
-            calc.p_t = eq.c;
+        calc
+    }
+
+    /// Checks if the solved calculation is complete and consistent. Returns an error of kind
+    /// Overconstrained if the values of a relation contradict each other or Underconstrained if
+    /// any field is still missing.
+    pub fn check(&self) -> crate::error::Result<()> {
+        let values = |f: Field| self.get(f).as_option();
+
+        for relation in relations().iter() {
+            if let (Some(a), Some(b)) = (relation.lhs.eval(&values), relation.rhs.eval(&values)) {
+                if (a - b).abs() > TOLERANCE * a.abs().max(b.abs()) {
+                    let fields = relation.variables();
+                    return Err(Error::new(Overconstrained, "The values contradict each other")
+                        .with_relation(relation)
+                        .with_values(fields.iter().filter_map(|f| values(*f).map(|v| (*f, v))).collect())
+                        .with_fields(fields)
+                        .with_residual(a - b));
+                }
+            }
         }
 
-        if let Ok(eq) = Equation::new(calc.n_t * (2.0 * PI / 60.0), Op::Mul, calc.m_t, calc.p_t).solve() {
-            calc.n_t = eq.a / (2.0 * PI / 60.0);
-            calc.m_t = eq.b;
-            calc.p_t = eq.c;
+        let missing = Field::ALL.iter()
+            .copied()
+            .filter(|f| self.get(*f).is_none())
+            .collect::<Vec<Field>>();
+        if !missing.is_empty() {
+            return Err(Error::new(Underconstrained, "Not all values could be calculated").with_fields(missing));
         }
 
-        Ok(calc)
+        Ok(())
     }
 
     /// Sets all values that are of type Num::Out to Num::None.
     pub fn clear_output(&self) -> Self {
         let mut calc = *self;
 
-        if calc.u.is_output() { calc.u = Num::None; }
-        if calc.i.is_output() { calc.i = Num::None; }
-        if calc.r_a.is_output() { calc.r_a = Num::None; }
-        if calc.p_in.is_output() { calc.p_in = Num::None; }
-        if calc.p_m.is_output() { calc.p_m = Num::None; }
-        if calc.p_m_l.is_output() { calc.p_m_l = Num::None; }
-        if calc.p_m_l_el.is_output() { calc.p_m_l_el = Num::None; }
-        if calc.p_m_l_mech.is_output() { calc.p_m_l_mech = Num::None; }
-        if calc.eta_m.is_output() { calc.eta_m = Num::None; }
-        if calc.m_m.is_output() { calc.m_m = Num::None; }
-        if calc.n_m.is_output() { calc.n_m = Num::None; }
-        if calc.i_t.is_output() { calc.i_t = Num::None; }
-        if calc.p_t.is_output() { calc.p_t = Num::None; }
-        if calc.p_t_l.is_output() { calc.p_t_l = Num::None; }
-        if calc.eta_t.is_output() { calc.eta_t = Num::None; }
-        if calc.m_t.is_output() { calc.m_t = Num::None; }
-        if calc.n_t.is_output() { calc.n_t = Num::None; }
+        for f in Field::ALL.iter() {
+            let num = calc.get_mut(*f);
+            if num.is_output() {
+                *num = Num::None;
+            }
+        }
 
         calc
     }
 }
 
+impl Default for Calculation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<Field> for Calculation {
+    type Output = Num;
+
+    fn index(&self, field: Field) -> &Num {
+        &self.values[field.index()]
+    }
+}
+
+impl IndexMut<Field> for Calculation {
+    fn index_mut(&mut self, field: Field) -> &mut Num {
+        &mut self.values[field.index()]
+    }
+}
+
+impl Serialize for Calculation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(Field::ALL.iter().map(|f| (f.id(), self[*f])))
+    }
+}
+
+impl<'de> Deserialize<'de> for Calculation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(CalculationVisitor)
+    }
+}
+
+/// Deserializes a calculation from a map of field ids to values.
+struct CalculationVisitor;
+
+impl<'de> Visitor<'de> for CalculationVisitor {
+    type Value = Calculation;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of field ids to numbers")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Calculation, A::Error> {
+        let mut calc = Calculation::new();
+
+        while let Some(id) = map.next_key::<String>()? {
+            match Field::from_id(&id) {
+                Some(f) => calc[f] = map.next_value()?,
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(calc)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use rand::Rng;
 
     use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::*;
     use crate::calc::number::Num;
+    use crate::error::ErrorKind::{Overconstrained, Underconstrained};
 
     #[test]
     fn test_calculation1() {
@@ -170,18 +261,95 @@ mod test {
         let second = rng.gen();
 
         let mut calc1 = Calculation::new();
-        calc1.u = Num::In(first);
-        calc1.i = Num::In(second);
-        assert_eq!(calc1.try_fill_missing().unwrap().p_in.num(), second * first);
+        calc1[Voltage] = Num::In(first);
+        calc1[Current] = Num::In(second);
+        assert_eq!(calc1.fill_missing()[InputPower].num(), second * first);
 
         let mut calc2 = Calculation::new();
-        calc2.u = Num::In(first);
-        calc2.p_in = Num::In(second);
-        assert_eq!(calc2.try_fill_missing().unwrap().i.num(), second / first);
+        calc2[Voltage] = Num::In(first);
+        calc2[InputPower] = Num::In(second);
+        assert_eq!(calc2.fill_missing()[Current].num(), second / first);
 
         let mut calc3 = Calculation::new();
-        calc3.i = Num::In(first);
-        calc3.p_in = Num::In(second);
-        assert_eq!(calc3.try_fill_missing().unwrap().u.num(), second / first);
+        calc3[Current] = Num::In(first);
+        calc3[InputPower] = Num::In(second);
+        assert_eq!(calc3.fill_missing()[Voltage].num(), second / first);
+    }
+
+    #[test]
+    fn test_not_finite() {
+        assert_eq!(Num::parse("NaN"), Num::None);
+        assert_eq!(Num::parse("inf"), Num::None);
+        assert_eq!(Num::parse("1e308k"), Num::None);
+
+        // values that never equal themselves don't keep the solver busy
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(f64::NAN);
+        calc[Current] = Num::In(2.5);
+        let (solved, _) = calc.solve();
+        assert!(solved[InputPower].is_none());
+        assert!(calc.solutions().len() <= 1);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(12.0);
+        calc[Current] = Num::In(2.5);
+        calc[ArmatureResistance] = Num::In(0.5);
+        calc[MotorEfficiency] = Num::In(80.0);
+        calc[TransmissionSpeed] = Num::In(300.0);
+        calc[TransmissionEfficiency] = Num::In(90.0);
+        calc[TransmissionRatio] = Num::In(0.2);
+        match calc.fill_missing().check() {
+            Ok(()) => (),
+            Err(e) => panic!("Expected a complete calculation, got {:?}", e),
+        }
+
+        calc[TransmissionEfficiency] = Num::None;
+        match calc.fill_missing().check().err().unwrap().kind {
+            Underconstrained => (),
+            _ => panic!("Expected Error with ErrorKind Underconstrained")
+        }
+
+        let error = calc.fill_missing().check().err().unwrap();
+        assert_eq!(error.fields, vec![TransmissionLoss, TransmissionPower, TransmissionTorque, TransmissionEfficiency]);
+
+        calc[InputPower] = Num::In(31.0);
+        let error = calc.fill_missing().check().err().unwrap();
+        match error.kind {
+            Overconstrained => (),
+            _ => panic!("Expected Error with ErrorKind Overconstrained")
+        }
+        assert_eq!(error.relation.as_deref(), Some("u · i = p_in"));
+        assert_eq!(error.values, vec![(Voltage, 12.0), (Current, 2.5), (InputPower, 31.0)]);
+        assert_eq!(error.residual, Some(-1.0));
+    }
+
+    #[test]
+    fn test_solutions() {
+        let mut rng = rand::thread_rng();
+        let low: f64 = rng.gen_range(1.0, 10.0);
+        let high: f64 = rng.gen_range(12.0, 40.0);
+
+        // U · I - I² · R_A = P_M has a low and a high current solution
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(0.5 * (low + high));
+        calc[ArmatureResistance] = Num::In(0.5);
+        calc[MotorPower] = Num::In(0.5 * low * high);
+        calc[MechanicalMotorLoss] = Num::In(0.0);
+
+        let solutions = calc.solutions();
+        assert_eq!(solutions.len(), 2);
+        assert!((solutions[0][Current].num() - low).abs() < 1e-8 * low);
+        assert!((solutions[1][Current].num() - high).abs() < 1e-8 * high);
+        assert!(solutions.iter().all(|c| c[InputPower].is_output() && c.is_physical()));
+        assert!(solutions[0][MotorEfficiency].num() > solutions[1][MotorEfficiency].num());
+
+        calc[MotorPower] = Num::In(-1.0);
+        calc[MechanicalMotorLoss] = Num::None;
+        calc[Current] = Num::In(2.0);
+        assert!(!calc.fill_missing().is_physical());
+        assert!(calc.solutions().is_empty());
     }
 }
@@ -0,0 +1,48 @@
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+
+/// Returns the difference of the number relative to the base in percent or None if one of them
+/// is missing or the base is zero.
+pub fn difference(base: Num, other: Num) -> Option<f64> {
+    let base = base.as_option().filter(|b| *b != 0.0)?;
+    let other = other.as_option()?;
+
+    Some((other - base) / base.abs() * 100.0)
+}
+
+/// Returns every field and its relative difference in percent of the other calculation compared
+/// to the base.
+pub fn differences(base: &Calculation, other: &Calculation) -> Vec<(Field, Option<f64>)> {
+    Field::ALL.iter()
+        .map(|f| (*f, difference(base.get(*f), other.get(*f))))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::calculation::Calculation;
+    use crate::calc::compare::{difference, differences};
+    use crate::calc::field::Field::{ArmatureResistance, Current, InputPower, Voltage};
+    use crate::calc::number::Num;
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(difference(Num::In(12.0), Num::Out(24.0)), Some(100.0));
+        assert_eq!(difference(Num::In(-10.0), Num::In(-5.0)), Some(50.0));
+        assert_eq!(difference(Num::In(0.0), Num::In(1.0)), None);
+        assert_eq!(difference(Num::None, Num::In(1.0)), None);
+
+        let mut a = Calculation::new();
+        a[Voltage] = Num::In(12.0);
+        a[Current] = Num::In(2.0);
+        let mut b = a;
+        b[Voltage] = Num::In(24.0);
+
+        let diffs = differences(&a.fill_missing(), &b.fill_missing());
+        assert!(diffs.contains(&(Voltage, Some(100.0))));
+        assert!(diffs.contains(&(Current, Some(0.0))));
+        assert!(diffs.contains(&(InputPower, Some(100.0))));
+        assert!(diffs.contains(&(ArmatureResistance, None)));
+    }
+}
@@ -0,0 +1,381 @@
+use std::fmt;
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+use crate::calc::root::{bisect, bracket};
+use crate::error::Error;
+use crate::error::ErrorKind::{DivisionByZero, NotFinite, Overconstrained, Underconstrained, Unreachable};
+
+/// A enum representing a expression tree built from constants and the fields of a calculation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Const(f64),
+    Var(Field),
+    Sum(Vec<Expr>),
+    Product(Vec<Expr>),
+    /// The base raised to a constant exponent. Roots and divisions are represented by fractional
+    /// and negative exponents.
+    Pow(Box<Expr>, f64),
+}
+
+impl Expr {
+    pub fn var(field: Field) -> Self {
+        Expr::Var(field)
+    }
+
+    pub fn constant(v: f64) -> Self {
+        Expr::Const(v)
+    }
+
+    pub fn sum(terms: Vec<Expr>) -> Self {
+        Expr::Sum(terms)
+    }
+
+    pub fn product(factors: Vec<Expr>) -> Self {
+        Expr::Product(factors)
+    }
+
+    pub fn pow(base: Expr, exponent: f64) -> Self {
+        Expr::Pow(Box::new(base), exponent)
+    }
+
+    pub fn sqrt(base: Expr) -> Self {
+        Expr::pow(base, 0.5)
+    }
+
+    /// Evaluates the expression with the values of the variables. Returns None if a variable has
+    /// no value or the result isn't a finite number.
+    pub fn eval(&self, values: &dyn Fn(Field) -> Option<f64>) -> Option<f64> {
+        let v = match self {
+            Expr::Const(v) => *v,
+            Expr::Var(f) => values(*f)?,
+            Expr::Sum(terms) => terms.iter().map(|t| t.eval(values)).sum::<Option<f64>>()?,
+            Expr::Product(factors) => factors.iter().map(|f| f.eval(values)).product::<Option<f64>>()?,
+            Expr::Pow(base, exponent) => base.eval(values)?.powf(*exponent),
+        };
+
+        Some(v).filter(|v| v.is_finite())
+    }
+
+    /// Returns the fields of all variables in the order of their first occurrence.
+    pub fn variables(&self) -> Vec<Field> {
+        let mut fields = Vec::new();
+        self.collect_variables(&mut fields);
+        fields
+    }
+
+    fn collect_variables(&self, fields: &mut Vec<Field>) {
+        match self {
+            Expr::Const(_) => (),
+            Expr::Var(f) => {
+                if !fields.contains(f) {
+                    fields.push(*f);
+                }
+            }
+            Expr::Sum(children) | Expr::Product(children) => children.iter().for_each(|c| c.collect_variables(fields)),
+            Expr::Pow(base, _) => base.collect_variables(fields),
+        }
+    }
+
+    /// Returns how often the variable occurs in the expression.
+    pub fn occurrences(&self, field: Field) -> usize {
+        match self {
+            Expr::Const(_) => 0,
+            Expr::Var(f) => (*f == field) as usize,
+            Expr::Sum(children) | Expr::Product(children) => children.iter().map(|c| c.occurrences(field)).sum(),
+            Expr::Pow(base, _) => base.occurrences(field),
+        }
+    }
+
+    /// Returns the value of the variable for which the expression equals the target,
+    /// by inverting the operations of the expression. Even powers are inverted to the positive
+    /// root. Returns a Error of kind Unreachable if the variable occurs more than once or an
+    /// operation can't be inverted, DivisionByZero if a factor of the variable is zero or
+    /// NotFinite if the result or a known part of the expression isn't a finite number.
+    pub fn isolate(&self, field: Field, target: f64, values: &dyn Fn(Field) -> Option<f64>) -> crate::error::Result<f64> {
+        let not_invertible = || Error::new(Unreachable, "The field can't be isolated");
+        let not_finite = || Error::new(NotFinite, "The result isn't a finite number");
+
+        if self.occurrences(field) != 1 {
+            return Err(not_invertible());
+        }
+
+        let v = match self {
+            Expr::Const(_) => return Err(not_invertible()),
+            Expr::Var(_) => target,
+            Expr::Sum(terms) => {
+                let (unknown, known) = split(terms, field);
+                let rest = known.iter().map(|t| t.eval(values)).sum::<Option<f64>>().ok_or_else(not_finite)?;
+                unknown.isolate(field, target - rest, values)?
+            }
+            Expr::Product(factors) => {
+                let (unknown, known) = split(factors, field);
+                let rest = known.iter().map(|f| f.eval(values)).product::<Option<f64>>().ok_or_else(not_finite)?;
+                if rest == 0.0 {
+                    return Err(Error::new(DivisionByZero, "The field is multiplied by zero"));
+                }
+                unknown.isolate(field, target / rest, values)?
+            }
+            Expr::Pow(base, exponent) => {
+                if *exponent == 0.0 {
+                    return Err(not_invertible());
+                }
+                base.isolate(field, target.powf(1.0 / exponent), values)?
+            }
+        };
+
+        Some(v).filter(|v| v.is_finite()).ok_or_else(not_finite)
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Formats the expression like `n_m · 0.10472 · m_m`. Constants are rounded to 6 significant
+    /// figures.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let child = |c: &Expr, parenthesize: bool| match c {
+            Expr::Sum(_) | Expr::Product(_) if parenthesize => format!("({})", c),
+            _ => c.to_string(),
+        };
+
+        match self {
+            Expr::Const(v) => write!(f, "{}", format!("{:.5e}", v).parse::<f64>().unwrap_or(*v)),
+            Expr::Var(v) => write!(f, "{}", v.id()),
+            Expr::Sum(terms) => {
+                let terms = terms.iter().map(|t| child(t, false)).collect::<Vec<String>>();
+                write!(f, "{}", terms.join(" + "))
+            }
+            Expr::Product(factors) => {
+                let factors = factors.iter().map(|c| child(c, matches!(c, Expr::Sum(_)))).collect::<Vec<String>>();
+                write!(f, "{}", factors.join(" · "))
+            }
+            Expr::Pow(base, exponent) if *exponent == 2.0 => write!(f, "{}²", child(base, true)),
+            Expr::Pow(base, exponent) if *exponent == 0.5 => write!(f, "√{}", child(base, true)),
+            Expr::Pow(base, exponent) => write!(f, "{}^{}", child(base, true), exponent),
+        }
+    }
+}
+
+/// Splits the children into the one containing the variable and the others.
+fn split(children: &[Expr], field: Field) -> (&Expr, Vec<&Expr>) {
+    let index = children.iter().position(|c| c.occurrences(field) > 0).unwrap_or_default();
+    let others = children.iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, c)| c)
+        .collect();
+
+    (&children[index], others)
+}
+
+/// A structure representing a equation between two expressions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    pub lhs: Expr,
+    pub rhs: Expr,
+}
+
+impl Relation {
+    pub fn new(lhs: Expr, rhs: Expr) -> Self {
+        Self { lhs, rhs }
+    }
+
+    /// Returns the fields of all variables of both sides.
+    pub fn variables(&self) -> Vec<Field> {
+        let mut fields = self.lhs.variables();
+        for f in self.rhs.variables() {
+            if !fields.contains(&f) {
+                fields.push(f);
+            }
+        }
+        fields
+    }
+
+    /// Returns the difference of both sides or None if a variable has no value.
+    pub fn residual(&self, values: &dyn Fn(Field) -> Option<f64>) -> Option<f64> {
+        Some(self.lhs.eval(values)? - self.rhs.eval(values)?)
+    }
+
+    /// Calculates the value of the only variable without a value. The variable is isolated if it
+    /// occurs once, otherwise the root is searched numerically. Returns a Error of kind
+    /// Overconstrained if all variables have a value, Underconstrained if more than one is
+    /// missing, DivisionByZero or NotFinite if the isolation failed for that reason and no root
+    /// was found, or Unreachable otherwise. The error names the relation and the known values.
+    pub fn solve(&self, values: &dyn Fn(Field) -> Option<f64>) -> crate::error::Result<(Field, f64)> {
+        let variables = self.variables();
+        let known = variables.iter()
+            .filter_map(|f| values(*f).map(|v| (*f, v)))
+            .collect::<Vec<(Field, f64)>>();
+        let context = |e: Error| e.with_relation(self).with_fields(variables.clone()).with_values(known.clone());
+
+        let unknowns = variables.iter()
+            .copied()
+            .filter(|f| values(*f).is_none())
+            .collect::<Vec<Field>>();
+
+        let field = match unknowns.as_slice() {
+            [] => {
+                let error = Error::new(Overconstrained, "All values of the relation are known");
+                return Err(match self.residual(values) {
+                    Some(r) => context(error).with_residual(r),
+                    None => context(error),
+                });
+            }
+            [f] => *f,
+            _ => return Err(context(Error::new(Underconstrained, "More than one value of the relation is missing"))),
+        };
+
+        let not_finite = || Error::new(NotFinite, "The result isn't a finite number");
+        let isolated = match (self.lhs.occurrences(field), self.rhs.occurrences(field)) {
+            (1, 0) => self.rhs.eval(values).ok_or_else(not_finite).and_then(|t| self.lhs.isolate(field, t, values)),
+            (0, 1) => self.lhs.eval(values).ok_or_else(not_finite).and_then(|t| self.rhs.isolate(field, t, values)),
+            _ => Err(Error::new(Unreachable, "The field occurs more than once")),
+        };
+
+        match isolated {
+            Ok(v) => Ok((field, v)),
+            Err(e) => self.solve_numerically(field, values)
+                .map(|v| (field, v))
+                .ok_or_else(|| match e.kind {
+                    DivisionByZero | NotFinite => context(e),
+                    _ => context(Error::new(Unreachable, "The relation has no solution")),
+                }),
+        }
+    }
+
+    /// Searches the root of the residual in the unknown variable.
+    fn solve_numerically(&self, field: Field, values: &dyn Fn(Field) -> Option<f64>) -> Option<f64> {
+        let residual = |x: f64| self.residual(&|f: Field| if f == field { Some(x) } else { values(f) });
+        let (lower, upper) = bracket(residual, 1.0)?;
+
+        bisect(residual, lower, upper)
+    }
+
+    /// Solves the relation with the values of the calculation and assigns the result to the
+    /// missing field.
+    pub fn solve_and_assign(&self, calc: &mut Calculation) {
+        let values = *calc;
+        if let Ok((field, v)) = self.solve(&|f: Field| values.get(f).as_option()) {
+            *calc.get_mut(field) = Num::Out(v);
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.lhs, self.rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::expression::{Expr, Relation};
+    use crate::calc::field::Field;
+    use crate::error::ErrorKind::{DivisionByZero, NotFinite, Overconstrained, Underconstrained};
+
+    // any fields can be used as variables
+    const X: Field = Field::Voltage;
+    const Y: Field = Field::Current;
+    const Z: Field = Field::InputPower;
+
+    fn values(x: Option<f64>, y: Option<f64>, z: Option<f64>) -> impl Fn(Field) -> Option<f64> {
+        move |f: Field| match f {
+            X => x,
+            Y => y,
+            Z => z,
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_eval() {
+        let expr = Expr::sum(vec![
+            Expr::product(vec![Expr::constant(2.0), Expr::pow(Expr::var(X), 2.0)]),
+            Expr::sqrt(Expr::var(Y)),
+            Expr::pow(Expr::var(Z), -1.0),
+        ]);
+
+        assert_eq!(expr.eval(&values(Some(3.0), Some(16.0), Some(0.5))), Some(24.0));
+        assert_eq!(expr.eval(&values(Some(3.0), None, Some(0.5))), None);
+        assert_eq!(expr.eval(&values(Some(3.0), Some(16.0), Some(0.0))), None);
+        assert_eq!(expr.variables(), vec![X, Y, Z]);
+    }
+
+    #[test]
+    fn test_isolate() {
+        let mut rng = rand::thread_rng();
+        let x: f64 = rng.gen_range(0.1, 100.0);
+        let y: f64 = rng.gen_range(0.1, 100.0);
+
+        // x² · y = z
+        let relation = Relation::new(
+            Expr::product(vec![Expr::pow(Expr::var(X), 2.0), Expr::var(Y)]),
+            Expr::var(Z),
+        );
+
+        let (field, z) = relation.solve(&values(Some(x), Some(y), None)).unwrap();
+        assert_eq!(field, Z);
+        assert!((z - x * x * y).abs() < 1e-9 * z);
+
+        let (field, v) = relation.solve(&values(None, Some(y), Some(z))).unwrap();
+        assert_eq!(field, X);
+        assert!((v - x).abs() < 1e-9 * x);
+
+        let (_, v) = relation.solve(&values(Some(x), None, Some(z))).unwrap();
+        assert!((v - y).abs() < 1e-9 * y);
+
+        match relation.solve(&values(Some(x), Some(y), Some(z))).unwrap_err().kind {
+            Overconstrained => (),
+            _ => panic!("Expected Error with ErrorKind Overconstrained"),
+        }
+        match relation.solve(&values(Some(x), None, None)).unwrap_err().kind {
+            Underconstrained => (),
+            _ => panic!("Expected Error with ErrorKind Underconstrained"),
+        }
+    }
+
+    #[test]
+    fn test_numeric_fallback() {
+        // x + x² = y
+        let relation = Relation::new(
+            Expr::sum(vec![Expr::var(X), Expr::pow(Expr::var(X), 2.0)]),
+            Expr::var(Y),
+        );
+        assert!(relation.lhs.isolate(X, 6.0, &values(None, Some(6.0), None)).is_err());
+
+        let (field, x) = relation.solve(&values(None, Some(6.0), None)).unwrap();
+        assert_eq!(field, X);
+        assert!((x - 2.0).abs() < 1e-12);
+        assert!(relation.solve(&values(None, Some(-1.0), None)).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        // x · y = z
+        let relation = Relation::new(Expr::product(vec![Expr::var(X), Expr::var(Y)]), Expr::var(Z));
+        assert_eq!(relation.to_string(), "u · i = p_in");
+
+        let error = relation.solve(&values(None, Some(0.0), Some(5.0))).unwrap_err();
+        match error.kind {
+            DivisionByZero => (),
+            _ => panic!("Expected Error with ErrorKind DivisionByZero"),
+        }
+        assert_eq!(error.relation.as_deref(), Some("u · i = p_in"));
+        assert_eq!(error.values, vec![(Y, 0.0), (Z, 5.0)]);
+
+        let error = relation.solve(&values(Some(2.0), Some(3.0), Some(7.0))).unwrap_err();
+        assert_eq!(error.residual, Some(-1.0));
+
+        let root = Relation::new(Expr::sqrt(Expr::var(X)), Expr::var(Y));
+        assert_eq!(root.to_string(), "√u = i");
+        match root.solve(&values(None, Some(f64::MAX), None)).unwrap_err().kind {
+            NotFinite => (),
+            _ => panic!("Expected Error with ErrorKind NotFinite"),
+        }
+
+        let w = Expr::product(vec![Expr::sum(vec![Expr::var(X), Expr::constant(1.0)]), Expr::constant(std::f64::consts::PI / 30.0)]);
+        assert_eq!(w.to_string(), "(u + 1) · 0.10472");
+    }
+}
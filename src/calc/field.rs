@@ -0,0 +1,97 @@
+use crate::calc::number::Num;
+pub use crate::calc::registry::Field;
+use crate::calc::registry::quantity_of;
+
+/// A enum representing how the value of a field is entered and displayed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Notation {
+    Number,
+    /// A ratio like 5:1, the value is the second number divided by the first.
+    Ratio,
+}
+
+impl Field {
+    /// Returns the field with the id or None if there is no such field.
+    pub fn from_id(id: &str) -> Option<Field> {
+        Field::ALL.iter().copied().find(|f| f.id() == id)
+    }
+
+    /// Returns the position of the field in Field::ALL.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the id used in files, links, CSV headers and on the command line, like `p_m_l_el`.
+    pub fn id(self) -> &'static str {
+        quantity_of(self).id
+    }
+
+    pub fn symbol(self) -> &'static str {
+        quantity_of(self).symbol
+    }
+
+    pub fn sub(self) -> &'static str {
+        quantity_of(self).sub
+    }
+
+    pub fn unit(self) -> &'static str {
+        quantity_of(self).unit
+    }
+
+    pub fn description(self) -> &'static str {
+        quantity_of(self).description
+    }
+
+    pub fn notation(self) -> Notation {
+        quantity_of(self).notation
+    }
+
+    /// Returns the column and row of the field in the grid of the forms.
+    pub fn position(self) -> (u16, u16) {
+        quantity_of(self).position
+    }
+
+    /// Parses the input of the field according to its notation.
+    pub fn parse(self, s: impl Into<String>) -> Num {
+        match self.notation() {
+            Notation::Ratio => Num::parse_ratio(s),
+            Notation::Number => Num::parse(s),
+        }
+    }
+
+    /// Formats the number so that it can be edited and parsed again as the input of the field.
+    pub fn format(self, num: Num) -> String {
+        match (self.notation(), num) {
+            (_, Num::None) => String::new(),
+            (Notation::Ratio, n) => n.display_ratio(),
+            (Notation::Number, n) => n.num().to_string(),
+        }
+    }
+
+    /// Formats the number for display with the specified number of significant figures
+    /// according to the notation of the field.
+    pub fn display(self, num: Num, significant_figures: usize) -> String {
+        match self.notation() {
+            Notation::Ratio => num.display_ratio(),
+            Notation::Number => num.display(significant_figures),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::field::Field;
+    use crate::calc::number::Num;
+
+    #[test]
+    fn test_field() {
+        assert!(Field::ALL.iter().enumerate().all(|(i, f)| f.index() == i));
+        assert!(Field::ALL.iter().all(|f| Field::from_id(f.id()) == Some(*f)));
+        assert_eq!(Field::from_id("x"), None);
+
+        assert_eq!(Field::TransmissionRatio.parse("5:1"), Num::In(0.2));
+        assert_eq!(Field::TransmissionRatio.format(Num::In(0.2)), "5:1");
+        assert_eq!(Field::Voltage.format(Num::In(12.5)), "12.5");
+        assert_eq!(Field::Voltage.format(Num::None), "");
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::calc::calculation::Calculation;
+use crate::error::Error;
+use crate::error::ErrorKind::{Format, Version};
+
+/// The version of the file format. It has to be increased whenever a change to the format would
+/// make older versions of this crate misinterpret the file, adding fields doesn't require that.
+pub const VERSION: u32 = 1;
+
+/// A structure representing a saved calculation.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct File {
+    version: u32,
+    calculation: Calculation,
+}
+
+impl File {
+    fn new(calc: &Calculation) -> Self {
+        Self { version: VERSION, calculation: *calc }
+    }
+
+    /// Returns the calculation if the file was written in a supported version.
+    fn calculation(self) -> crate::error::Result<Calculation> {
+        if self.version > VERSION {
+            return Err(Error::new(Version, "The file was saved by a newer version"));
+        }
+
+        Ok(self.calculation)
+    }
+}
+
+/// Serializes the calculation to a JSON string.
+pub fn to_json(calc: &Calculation) -> crate::error::Result<String> {
+    serde_json::to_string_pretty(&File::new(calc))
+        .map_err(|_| Error::new(Format, "The calculation couldn't be serialized to JSON"))
+}
+
+/// Deserializes a calculation from a JSON string.
+pub fn from_json(s: &str) -> crate::error::Result<Calculation> {
+    serde_json::from_str::<File>(s)
+        .map_err(|_| Error::new(Format, "The file isn't a valid JSON calculation"))?
+        .calculation()
+}
+
+/// Serializes the calculation to a TOML string.
+pub fn to_toml(calc: &Calculation) -> crate::error::Result<String> {
+    toml::to_string_pretty(&File::new(calc))
+        .map_err(|_| Error::new(Format, "The calculation couldn't be serialized to TOML"))
+}
+
+/// Deserializes a calculation from a TOML string.
+pub fn from_toml(s: &str) -> crate::error::Result<Calculation> {
+    toml::from_str::<File>(s)
+        .map_err(|_| Error::new(Format, "The file isn't a valid TOML calculation"))?
+        .calculation()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{ArmatureResistance, Current, TransmissionRatio, Voltage};
+    use crate::calc::file::{from_json, from_toml, to_json, to_toml};
+    use crate::calc::number::Num;
+    use crate::error::ErrorKind::Version;
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(rng.gen());
+        calc[Current] = Num::In(rng.gen());
+        calc[TransmissionRatio] = Num::In(0.2);
+        let calc = calc.fill_missing();
+
+        assert_eq!(from_json(&to_json(&calc).unwrap()).unwrap(), calc);
+        assert_eq!(from_toml(&to_toml(&calc).unwrap()).unwrap(), calc);
+    }
+
+    #[test]
+    fn test_compatibility() {
+        let json = r#"{
+            "version": 1,
+            "calculation": {
+                "u": { "type": "in", "value": 12.0 },
+                "i": { "type": "out", "value": 2.5 },
+                "unknown": { "type": "none" }
+            }
+        }"#;
+        let calc = from_json(json).unwrap();
+        assert_eq!(calc[Voltage], Num::In(12.0));
+        assert_eq!(calc[Current], Num::Out(2.5));
+        assert_eq!(calc[ArmatureResistance], Num::None);
+
+        let toml = "version = 2\n[calculation]\nu = { type = \"in\", value = 12.0 }\n";
+        match from_toml(toml).err().unwrap().kind {
+            Version => (),
+            _ => panic!("Expected Error with ErrorKind Version")
+        }
+    }
+}
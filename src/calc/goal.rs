@@ -0,0 +1,109 @@
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+use crate::calc::root::{bisect, bracket};
+use crate::error::Error;
+use crate::error::ErrorKind::Unreachable;
+
+/// A structure describing the value an output should reach by varying an input.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Goal {
+    /// The varied input.
+    pub input: Field,
+    /// The output that should reach the target.
+    pub output: Field,
+    pub target: f64,
+}
+
+impl Goal {
+    pub fn new(input: Field, output: Field, target: f64) -> Self {
+        Self { input, output, target }
+    }
+
+    /// Varies the input between the bounds by bisection until the output reaches the target. The
+    /// output has to be above the target at one and below it at the other bound.
+    pub fn seek_between(&self, calc: &Calculation, lower: f64, upper: f64) -> crate::error::Result<Calculation> {
+        let calc = self.prepare(calc);
+        let x = bisect(|x| self.residual(&calc, x), lower, upper)
+            .ok_or_else(|| Error::new(Unreachable, "The target isn't reached within the range"))?;
+
+        Ok(solve(&calc, self.input, x))
+    }
+
+    /// Varies the input until the output reaches the target. The range is searched outwards from
+    /// the current value of the input.
+    pub fn seek(&self, calc: &Calculation) -> crate::error::Result<Calculation> {
+        let prepared = self.prepare(calc);
+        let start = calc.get(self.input).as_option().unwrap_or(1.0);
+        let (lower, upper) = bracket(|x| self.residual(&prepared, x), start)
+            .ok_or_else(|| Error::new(Unreachable, "No value of the input reaches the target"))?;
+
+        self.seek_between(calc, lower, upper)
+    }
+
+    /// Returns the calculation with the input and the output cleared so that they can be
+    /// calculated.
+    fn prepare(&self, calc: &Calculation) -> Calculation {
+        let mut calc = calc.clear_output();
+        *calc.get_mut(self.input) = Num::None;
+        *calc.get_mut(self.output) = Num::None;
+
+        calc
+    }
+
+    /// Returns the difference of the output to the target for the value of the input.
+    fn residual(&self, calc: &Calculation, x: f64) -> Option<f64> {
+        let calc = solve(calc, self.input, x);
+
+        Some(calc.get(self.output).as_option()? - self.target)
+    }
+}
+
+/// Solves the calculation with the input set to the value.
+fn solve(calc: &Calculation, input: Field, x: f64) -> Calculation {
+    let mut calc = *calc;
+    *calc.get_mut(input) = Num::In(x);
+
+    calc.fill_missing()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{ArmatureResistance, Current, ElectricalMotorLoss, InputPower, Voltage};
+    use crate::calc::goal::Goal;
+    use crate::calc::number::Num;
+    use crate::error::ErrorKind::Unreachable;
+
+    #[test]
+    fn test_seek() {
+        let mut rng = rand::thread_rng();
+        let target: f64 = rng.gen_range(1.0, 100.0);
+
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(12.0);
+        calc[Current] = Num::In(2.0);
+        calc[ArmatureResistance] = Num::In(0.5);
+        let calc = calc.fill_missing();
+
+        let solved = Goal::new(Current, ElectricalMotorLoss, target).seek(&calc).unwrap();
+        assert!(solved[Current].is_input());
+        assert!(solved[ElectricalMotorLoss].is_output());
+        assert!((solved[ElectricalMotorLoss].num() - target).abs() < 1e-9 * target);
+        assert!((solved[Current].num() - (target / 0.5).sqrt()).abs() < 1e-9);
+
+        let solved = Goal::new(Voltage, InputPower, 30.0).seek_between(&calc, 0.0, 100.0).unwrap();
+        assert!((solved[Voltage].num() - 15.0).abs() < 1e-9);
+
+        match Goal::new(Voltage, InputPower, 300.0).seek_between(&calc, 0.0, 100.0).unwrap_err().kind {
+            Unreachable => (),
+            _ => panic!("Expected Error with ErrorKind Unreachable"),
+        }
+        match Goal::new(Current, ElectricalMotorLoss, -1.0).seek(&calc).unwrap_err().kind {
+            Unreachable => (),
+            _ => panic!("Expected Error with ErrorKind Unreachable"),
+        }
+    }
+}
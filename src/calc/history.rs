@@ -0,0 +1,86 @@
+use crate::calc::calculation::Calculation;
+
+/// The maximum number of snapshots that can be undone.
+const LIMIT: usize = 100;
+
+/// A structure holding snapshots of a calculation that allows undoing and redoing changes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    undo: Vec<Calculation>,
+    redo: Vec<Calculation>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self { undo: Vec::new(), redo: Vec::new() }
+    }
+
+    /// Records the calculation as it was before a change. Changes that were undone can't be
+    /// redone afterwards.
+    pub fn push(&mut self, calc: Calculation) {
+        if self.undo.last() != Some(&calc) {
+            self.undo.push(calc);
+        }
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the calculation before the last change and records the current one for redoing.
+    pub fn undo(&mut self, current: Calculation) -> Option<Calculation> {
+        let calc = self.undo.pop()?;
+        self.redo.push(current);
+        Some(calc)
+    }
+
+    /// Returns the calculation before the last undo and records the current one for undoing.
+    pub fn redo(&mut self, current: Calculation) -> Option<Calculation> {
+        let calc = self.redo.pop()?;
+        self.undo.push(current);
+        Some(calc)
+    }
+
+    /// Returns true if there is a change to undo false otherwise.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns true if there is a change to redo false otherwise.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{Current, Voltage};
+    use crate::calc::history::History;
+    use crate::calc::number::Num;
+
+    #[test]
+    fn test_undo_redo() {
+        let first = Calculation::new();
+        let mut second = first;
+        second[Voltage] = Num::In(12.0);
+        let mut third = second;
+        third[Current] = Num::In(2.0);
+
+        let mut history = History::new();
+        assert!(!history.can_undo());
+        history.push(first);
+        history.push(second);
+
+        assert_eq!(history.undo(third), Some(second));
+        assert_eq!(history.undo(second), Some(first));
+        assert_eq!(history.undo(first), None);
+        assert_eq!(history.redo(first), Some(second));
+        assert_eq!(history.redo(second), Some(third));
+        assert!(!history.can_redo());
+
+        history.undo(third);
+        history.push(second);
+        assert!(!history.can_redo());
+    }
+}
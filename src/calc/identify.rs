@@ -0,0 +1,200 @@
+use std::f64::consts::PI;
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field::{Current, MotorSpeed, MotorTorque, Voltage};
+use crate::calc::newton::solve_linear;
+use crate::error::Error;
+use crate::error::ErrorKind::Underconstrained;
+
+/// The minimum number of points needed to fit two parameters with a remaining degree of freedom.
+pub const MIN_POINTS: usize = 3;
+
+/// A structure holding a measured operating point of a motor.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Point {
+    /// Voltage: U [V]
+    pub u: f64,
+    /// Current: I [A]
+    pub i: f64,
+    /// Motor speed: n<sub>M</sub> [rpm]
+    pub n_m: f64,
+    /// Motor torque: M<sub>M</sub> [Nm]
+    pub m_m: f64,
+}
+
+impl Point {
+    pub fn new(u: f64, i: f64, n_m: f64, m_m: f64) -> Self {
+        Self { u, i, n_m, m_m }
+    }
+
+    /// Returns the point of the calculation or None if one of U, I, n<sub>M</sub> and
+    /// M<sub>M</sub> is missing.
+    pub fn from_calculation(calc: &Calculation) -> Option<Self> {
+        Some(Self {
+            u: calc[Voltage].as_option()?,
+            i: calc[Current].as_option()?,
+            n_m: calc[MotorSpeed].as_option()?,
+            m_m: calc[MotorTorque].as_option()?,
+        })
+    }
+
+    /// Returns the angular velocity [rad/s].
+    fn omega(&self) -> f64 {
+        self.n_m * 2.0 * PI / 60.0
+    }
+}
+
+/// A structure describing how well a regression fits the measured values.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fit {
+    /// The coefficient of determination, 1 for a perfect fit.
+    pub r_squared: f64,
+    /// The root mean square error in the unit of the fitted value.
+    pub rmse: f64,
+}
+
+/// A structure holding the parameters of a DC motor fitted to measured points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Model {
+    /// Armature resistance: R<sub>A</sub> [Ω]
+    pub r_a: f64,
+    /// Torque constant: k<sub>t</sub> [Nm/A]
+    pub k_t: f64,
+    /// Back EMF constant: k<sub>e</sub> [Vs/rad]
+    pub k_e: f64,
+    /// No load current: I<sub>0</sub> [A]
+    pub i_0: f64,
+    /// Friction torque: M<sub>f</sub> [Nm]
+    pub m_f: f64,
+    /// The fit of U = R<sub>A</sub> · I + k<sub>e</sub> · ω.
+    pub voltage: Fit,
+    /// The fit of M<sub>M</sub> = k<sub>t</sub> · I - M<sub>f</sub>.
+    pub torque: Fit,
+}
+
+impl Model {
+    /// Returns the current and the voltage needed to deliver the torque [Nm] at the speed [rpm].
+    pub fn operating_point(&self, m_m: f64, n_m: f64) -> (f64, f64) {
+        let i = (m_m + self.m_f) / self.k_t;
+        let u = i * self.r_a + self.k_e * n_m * (2.0 * PI / 60.0);
+
+        (i, u)
+    }
+}
+
+/// Fits the parameters of a DC motor to the measured points by linear least squares. The voltage
+/// is regressed on the current and the angular velocity, the torque on the current with the
+/// friction torque as intercept. Returns a Error of kind Underconstrained if there are less than
+/// MIN_POINTS points or the points don't vary enough to separate the parameters.
+pub fn identify(points: &[Point]) -> crate::error::Result<Model> {
+    if points.len() < MIN_POINTS {
+        return Err(Error::new(Underconstrained, "At least 3 points are needed"));
+    }
+    let underconstrained = || Error::new(Underconstrained, "The points don't vary enough to separate the parameters");
+
+    let voltage_rows = points.iter().map(|p| vec![p.i, p.omega()]).collect::<Vec<Vec<f64>>>();
+    let voltages = points.iter().map(|p| p.u).collect::<Vec<f64>>();
+    let (v, voltage) = regress(&voltage_rows, &voltages, false).ok_or_else(underconstrained)?;
+
+    let torque_rows = points.iter().map(|p| vec![p.i, 1.0]).collect::<Vec<Vec<f64>>>();
+    let torques = points.iter().map(|p| p.m_m).collect::<Vec<f64>>();
+    let (t, torque) = regress(&torque_rows, &torques, true).ok_or_else(underconstrained)?;
+
+    let (k_t, m_f) = (t[0], -t[1]);
+    if k_t == 0.0 {
+        return Err(underconstrained());
+    }
+
+    Ok(Model {
+        r_a: v[0],
+        k_t,
+        k_e: v[1],
+        i_0: m_f / k_t,
+        m_f,
+        voltage,
+        torque,
+    })
+}
+
+/// Solves the normal equations of y = rows · coefficients. Returns None if the columns are
+/// linearly dependent. Without an intercept column the coefficient of determination is taken
+/// relative to zero instead of the mean, as usual for a regression through the origin.
+fn regress(rows: &[Vec<f64>], y: &[f64], intercept: bool) -> Option<(Vec<f64>, Fit)> {
+    let n = rows.first()?.len();
+    let ata = (0..n)
+        .map(|a| (0..n).map(|b| rows.iter().map(|r| r[a] * r[b]).sum()).collect())
+        .collect::<Vec<Vec<f64>>>();
+    let aty = (0..n)
+        .map(|a| rows.iter().zip(y).map(|(r, y)| r[a] * y).sum())
+        .collect::<Vec<f64>>();
+    let coefficients = solve_linear(ata, aty)?;
+
+    let mean = if intercept { y.iter().sum::<f64>() / y.len() as f64 } else { 0.0 };
+    let ss_res = rows.iter()
+        .zip(y)
+        .map(|(r, y)| y - r.iter().zip(&coefficients).map(|(x, c)| x * c).sum::<f64>())
+        .map(|e| e * e)
+        .sum::<f64>();
+    let ss_tot = y.iter().map(|y| (y - mean) * (y - mean)).sum::<f64>();
+
+    let fit = Fit {
+        r_squared: if ss_tot == 0.0 { 1.0 } else { 1.0 - ss_res / ss_tot },
+        rmse: (ss_res / y.len() as f64).sqrt(),
+    };
+
+    Some((coefficients, fit))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::identify::{identify, Point};
+    use crate::error::ErrorKind::Underconstrained;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9 * b.abs().max(1.0), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_identify() {
+        let mut rng = rand::thread_rng();
+        let r_a: f64 = rng.gen_range(0.1, 2.0);
+        let k: f64 = rng.gen_range(0.01, 0.5);
+        let m_f: f64 = rng.gen_range(0.001, 0.05);
+
+        let model = |n_m: f64, m_m: f64| {
+            let i = (m_m + m_f) / k;
+            Point::new(i * r_a + k * n_m * std::f64::consts::PI / 30.0, i, n_m, m_m)
+        };
+        let points = vec![model(3000.0, 0.1), model(2500.0, 0.3), model(1000.0, 0.4), model(500.0, 0.8)];
+
+        let fitted = identify(&points).unwrap();
+        assert_close(fitted.r_a, r_a);
+        assert_close(fitted.k_t, k);
+        assert_close(fitted.k_e, k);
+        assert_close(fitted.m_f, m_f);
+        assert_close(fitted.i_0, m_f / k);
+        assert_close(fitted.voltage.r_squared, 1.0);
+        assert!(fitted.torque.rmse < 1e-9);
+
+        let (i, u) = fitted.operating_point(0.4, 1000.0);
+        assert_close(i, points[2].i);
+        assert_close(u, points[2].u);
+
+        // measurement noise lowers the goodness of fit
+        let mut noisy = points.clone();
+        noisy[1].u *= 1.05;
+        let fitted = identify(&noisy).unwrap();
+        assert!(fitted.voltage.r_squared < 1.0 && fitted.voltage.rmse > 0.0);
+
+        match identify(&points[..2]).unwrap_err().kind {
+            Underconstrained => (),
+            _ => panic!("Expected Error with ErrorKind Underconstrained"),
+        }
+        match identify(&[points[0]; 3]).unwrap_err().kind {
+            Underconstrained => (),
+            _ => panic!("Expected Error with ErrorKind Underconstrained"),
+        }
+    }
+}
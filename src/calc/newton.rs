@@ -0,0 +1,335 @@
+use std::collections::BTreeSet;
+
+use crate::calc::calculation::Calculation;
+use crate::calc::expression::Relation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+use crate::calc::registry::relations;
+
+/// The maximum number of Newton iterations.
+const MAX_ITERATIONS: usize = 50;
+/// The maximum number of times a step is halved if it doesn't reduce the residuals.
+const MAX_DAMPING: usize = 20;
+/// The relative deviation up to which both sides of a relation are considered equal.
+const TOLERANCE: f64 = 1e-10;
+/// The maximum number of relations solved simultaneously.
+const MAX_SUBSYSTEM: usize = 5;
+/// The starting values of the search for all solutions.
+const STARTS: [f64; 9] = [1e-4, 1e-3, 1e-2, 1e-1, 1.0, 1e1, 1e2, 1e3, 1e4];
+/// The relative deviation above which two solutions are considered distinct.
+const DISTINCT: f64 = 1e-6;
+
+/// A enum representing the result of a simultaneous solve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Status {
+    Converged,
+    /// The maximum number of iterations was reached or a step couldn't reduce the residuals.
+    NotConverged,
+    /// The Jacobian was singular, so the relations don't determine the unknowns.
+    Singular,
+}
+
+/// A structure describing how a simultaneous solve went.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Report {
+    pub status: Status,
+    pub iterations: usize,
+    /// The largest relative residual of the relations at the end.
+    pub residual: f64,
+}
+
+impl Report {
+    /// Returns a sentence describing the status for display.
+    pub fn description(&self) -> String {
+        match self.status {
+            Status::Converged => format!("Solved simultaneously in {} iterations", self.iterations),
+            Status::NotConverged => format!("The simultaneous solve didn't converge, the largest relative residual is {:.1e}", self.residual),
+            Status::Singular => "The remaining values aren't determined by the inputs".to_string(),
+        }
+    }
+}
+
+/// Solves the relations for the unknowns simultaneously with the Newton-Raphson method starting
+/// at the given values. There have to be as many relations as unknowns.
+pub fn newton(relations: &[&Relation], unknowns: &[Field], values: &dyn Fn(Field) -> Option<f64>, start: &[f64]) -> (Vec<f64>, Report) {
+    let mut x = start.to_vec();
+    let mut report = Report { status: Status::NotConverged, iterations: 0, residual: f64::INFINITY };
+
+    let lookup = |x: &[f64], f: Field| match unknowns.iter().position(|u| *u == f) {
+        Some(i) => Some(x[i]),
+        None => values(f),
+    };
+    let residuals = |x: &[f64]| relations.iter()
+        .map(|r| r.residual(&|f: Field| lookup(x, f)))
+        .collect::<Option<Vec<f64>>>();
+    let error = |x: &[f64]| relations.iter()
+        .map(|r| {
+            let values = |f: Field| lookup(x, f);
+            let (a, b) = (r.lhs.eval(&values)?, r.rhs.eval(&values)?);
+            Some((a - b).abs() / a.abs().max(b.abs()).max(f64::MIN_POSITIVE))
+        })
+        .try_fold(0.0, |max, e| e.map(|e| e.max(max)))
+        .unwrap_or(f64::INFINITY);
+
+    for iteration in 0..=MAX_ITERATIONS {
+        report.iterations = iteration;
+        report.residual = error(&x);
+
+        if report.residual <= TOLERANCE {
+            report.status = Status::Converged;
+            return (x, report);
+        }
+        if iteration == MAX_ITERATIONS {
+            break;
+        }
+
+        let r = match residuals(&x) {
+            Some(r) => r,
+            None => break,
+        };
+
+        let mut jacobian = vec![vec![0.0; unknowns.len()]; relations.len()];
+        for j in 0..unknowns.len() {
+            // a backward difference is used if the relations can't be evaluated after the step
+            let shift = |h: f64| {
+                let mut shifted = x.clone();
+                shifted[j] += h;
+                residuals(&shifted).map(|r_h| (r_h, h))
+            };
+            let h = 1e-7 * x[j].abs().max(1.0);
+            let (r_h, h) = match shift(h).or_else(|| shift(-h)) {
+                Some(s) => s,
+                None => return (x, report),
+            };
+
+            for (i, row) in jacobian.iter_mut().enumerate() {
+                row[j] = (r_h[i] - r[i]) / h;
+            }
+        }
+
+        let step = match solve_linear(jacobian, r.iter().map(|v| -v).collect()) {
+            Some(s) => s,
+            None => {
+                report.status = Status::Singular;
+                return (x, report);
+            }
+        };
+
+        let mut factor = 1.0;
+        let mut next = None;
+        for _ in 0..MAX_DAMPING {
+            let candidate = x.iter().zip(&step).map(|(x, s)| x + factor * s).collect::<Vec<f64>>();
+            if error(&candidate) < report.residual {
+                next = Some(candidate);
+                break;
+            }
+            factor /= 2.0;
+        }
+
+        match next {
+            Some(n) => x = n,
+            None => break,
+        }
+    }
+
+    (x, report)
+}
+
+/// Searches for groups of relations that have as many missing fields as relations and can't be
+/// solved one after another, like when only η<sub>M</sub>, P<sub>ML</sub> and U are given, and
+/// solves them simultaneously. Returns the calculation with the fields of the first group that
+/// converged and the report of that group, or of the last one that was tried if none converged.
+/// Returns no report if there is no such group.
+pub fn solve_simultaneous(calc: &Calculation) -> (Calculation, Option<Report>) {
+    let relations = relations();
+    let values = |f: Field| calc.get(f).as_option();
+    let mut report = None;
+
+    for (members, unknowns) in groups(calc, relations) {
+        let (x, r) = newton(&members, &unknowns, &values, &vec![1.0; unknowns.len()]);
+        report = Some(r);
+
+        if r.status == Status::Converged {
+            return (assign(calc, &unknowns, &x), report);
+        }
+    }
+
+    (*calc, report)
+}
+
+/// Searches for every solution of the first group of relations that converges by starting the
+/// Newton-Raphson method at values spread over several orders of magnitude. Relations with a
+/// quadratic term, like U · I - I² · R<sub>A</sub> = P<sub>M</sub>, have two solutions. Returns
+/// the calculation with the fields of the group for every distinct solution, ordered by the value
+/// of the first unknown field, or no calculation if no group converged.
+pub fn branches(calc: &Calculation) -> Vec<Calculation> {
+    let relations = relations();
+    let values = |f: Field| calc.get(f).as_option();
+
+    for (members, unknowns) in groups(calc, relations) {
+        let mut solutions: Vec<Vec<f64>> = Vec::new();
+
+        for start in STARTS.iter() {
+            let (x, r) = newton(&members, &unknowns, &values, &vec![*start; unknowns.len()]);
+            let distinct = solutions.iter().all(|s| {
+                s.iter().zip(&x).any(|(a, b)| (a - b).abs() > DISTINCT * a.abs().max(b.abs()))
+            });
+
+            if r.status == Status::Converged && distinct {
+                solutions.push(x);
+            }
+        }
+
+        if !solutions.is_empty() {
+            solutions.sort_by(|a, b| a[0].partial_cmp(&b[0]).unwrap_or(std::cmp::Ordering::Equal));
+            return solutions.iter().map(|x| assign(calc, &unknowns, x)).collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Returns the groups of relations that have as many missing fields as relations, ordered by
+/// their size, together with the missing fields.
+fn groups<'a>(calc: &Calculation, relations: &'a [Relation]) -> Vec<(Vec<&'a Relation>, Vec<Field>)> {
+    let missing = relations.iter()
+        .map(|r| r.variables().into_iter().filter(|f| calc.get(*f) == Num::None).collect())
+        .collect::<Vec<BTreeSet<Field>>>();
+    let open = (0..relations.len()).filter(|i| !missing[*i].is_empty()).collect::<Vec<usize>>();
+
+    let mut groups = Vec::new();
+    for size in 2..=MAX_SUBSYSTEM {
+        extend(&missing, &open, size, &mut Vec::new(), &BTreeSet::new(), &mut groups);
+    }
+
+    groups.into_iter()
+        .map(|(members, unknowns)| (members.iter().map(|i| &relations[*i]).collect(), unknowns))
+        .collect()
+}
+
+/// Adds the groups of the size that extend the group by relations of the candidates. Groups
+/// with more missing fields than the size are dropped as soon as they have them.
+fn extend(
+    missing: &[BTreeSet<Field>],
+    candidates: &[usize],
+    size: usize,
+    group: &mut Vec<usize>,
+    unknowns: &BTreeSet<Field>,
+    groups: &mut Vec<(Vec<usize>, Vec<Field>)>,
+) {
+    if group.len() == size {
+        if unknowns.len() == size {
+            groups.push((group.clone(), unknowns.iter().copied().collect()));
+        }
+        return;
+    }
+
+    for (i, r) in candidates.iter().enumerate() {
+        let unknowns = unknowns.union(&missing[*r]).copied().collect::<BTreeSet<Field>>();
+        if unknowns.len() <= size {
+            group.push(*r);
+            extend(missing, &candidates[i + 1..], size, group, &unknowns, groups);
+            group.pop();
+        }
+    }
+}
+
+/// Returns the calculation with the values assigned to the unknown fields as outputs.
+fn assign(calc: &Calculation, unknowns: &[Field], x: &[f64]) -> Calculation {
+    let mut solved = *calc;
+    for (f, v) in unknowns.iter().zip(x) {
+        *solved.get_mut(*f) = Num::Out(*v);
+    }
+    solved
+}
+
+/// Solves the linear system a · x = b by Gaussian elimination with partial pivoting. Returns None
+/// if the matrix is singular.
+pub(crate) fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    let scale = a.iter().flatten().fold(0.0_f64, |m, v| m.max(v.abs()));
+
+    for k in 0..n {
+        let pivot = (k..n).max_by(|i, j| a[*i][k].abs().partial_cmp(&a[*j][k].abs()).unwrap_or(std::cmp::Ordering::Equal))?;
+        if a[pivot][k].abs() <= 1e-12 * scale {
+            return None;
+        }
+        a.swap(k, pivot);
+        b.swap(k, pivot);
+
+        let row = a[k].clone();
+        for i in k + 1..n {
+            let factor = a[i][k] / row[k];
+            for (v, p) in a[i][k..].iter_mut().zip(&row[k..]) {
+                *v -= factor * p;
+            }
+            b[i] -= factor * b[k];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for k in (0..n).rev() {
+        let sum = (k + 1..n).map(|j| a[k][j] * x[j]).sum::<f64>();
+        x[k] = (b[k] - sum) / a[k][k];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::expression::{Expr, Relation};
+    use crate::calc::field::Field::{Current, InputPower, MotorEfficiency, MotorLoss, MotorPower, Voltage};
+    use crate::calc::newton::{newton, solve_linear, solve_simultaneous, Status};
+    use crate::calc::number::Num;
+
+    #[test]
+    fn test_solve_linear() {
+        let x = solve_linear(vec![vec![0.0, 2.0], vec![3.0, 1.0]], vec![4.0, 5.0]).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+        assert_eq!(solve_linear(vec![vec![1.0, 2.0], vec![2.0, 4.0]], vec![1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn test_newton() {
+        // u · i = 6, u + i = 5
+        let product = Relation::new(Expr::product(vec![Expr::var(Voltage), Expr::var(Current)]), Expr::constant(6.0));
+        let sum = Relation::new(Expr::sum(vec![Expr::var(Voltage), Expr::var(Current)]), Expr::constant(5.0));
+
+        let (x, report) = newton(&[&product, &sum], &[Voltage, Current], &|_| None, &[0.0, 4.0]);
+        assert_eq!(report.status, Status::Converged);
+        assert!((x[0] - 2.0).abs() < 1e-9 && (x[1] - 3.0).abs() < 1e-9);
+
+        let (_, report) = newton(&[&sum, &sum], &[Voltage, Current], &|_| None, &[1.0, 1.0]);
+        assert_eq!(report.status, Status::Singular);
+    }
+
+    #[test]
+    fn test_solve_simultaneous() {
+        let mut rng = rand::thread_rng();
+        let eta_m: f64 = rng.gen_range(10.0, 95.0);
+        let p_m_l: f64 = rng.gen_range(1.0, 100.0);
+
+        let mut calc = Calculation::new();
+        calc[MotorEfficiency] = Num::In(eta_m);
+        calc[MotorLoss] = Num::In(p_m_l);
+        calc[Voltage] = Num::In(12.0);
+
+        let (solved, report) = solve_simultaneous(&calc);
+        assert_eq!(report.unwrap().status, Status::Converged);
+        let p_in = p_m_l / (1.0 - eta_m / 100.0);
+        assert!((solved[InputPower].num() - p_in).abs() < 1e-8 * p_in);
+        assert!(solved[MotorPower].is_output());
+
+        let (solved, report) = calc.solve();
+        assert_eq!(report.unwrap().status, Status::Converged);
+        assert!((solved[Current].num() - p_in / 12.0).abs() < 1e-8 * p_in);
+        assert!(solved.check().is_err());
+
+        let (_, report) = solve_simultaneous(&Calculation::new());
+        assert_eq!(report, None);
+    }
+}
@@ -1,5 +1,7 @@
 use std::ops::{Add, Div, Mul, Sub};
 
+use serde::{Deserialize, Serialize};
+
 /// Metric prefix and the factor.
 const METRIC_PREFIXES: [(&str, i32); 10] = [
     ("f", -15),
@@ -15,7 +17,8 @@ const METRIC_PREFIXES: [(&str, i32); 10] = [
 ];
 
 /// A enum that holds either an input, output or no number at all.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
 pub enum Num {
     In(f64),
     Out(f64),
@@ -25,26 +28,17 @@ pub enum Num {
 impl Num {
     /// Returns true if the number is an input false otherwise.
     pub fn is_input(&self) -> bool {
-        match self {
-            Num::In(_) => true,
-            _ => false,
-        }
+        matches!(self, Num::In(_))
     }
 
     /// Returns true if the number is an output false otherwise.
     pub fn is_output(&self) -> bool {
-        match self {
-            Num::Out(_) => true,
-            _ => false,
-        }
+        matches!(self, Num::Out(_))
     }
 
     /// Returns true if the number is none false otherwise.
     pub fn is_none(&self) -> bool {
-        match self {
-            Num::None => true,
-            _ => false,
-        }
+        matches!(self, Num::None)
     }
 
     /// Returns true if the number is an input or an output false otherwise.
@@ -101,11 +95,7 @@ impl Num {
             }
 
             let integer_figures = num.abs().log10().floor() as usize + 1;
-            let floating_figures = if integer_figures > significant_figures {
-                0
-            } else {
-                significant_figures - integer_figures
-            };
+            let floating_figures = significant_figures.saturating_sub(integer_figures);
 
             format!("{0:.1$}{2}", num, floating_figures, metric_prefix)
         } else {
@@ -119,14 +109,13 @@ impl Num {
             let num = self.num();
             let mut temp = self.num();
             let mut a: i64 = 1;
-            let b: i64;
 
             while temp.fract() > 0.0001 && temp.fract() < 0.9999 {
                 a += 1;
                 temp += num;
             }
 
-            b = temp.round() as i64;
+            let b = temp.round() as i64;
 
             format!("{}:{}", a, b)
         } else {
@@ -149,10 +138,9 @@ impl Num {
             }
         }
 
-        if let Ok(v) = s.parse::<f64>() {
-            Num::In(v * factor)
-        } else {
-            Num::None
+        match s.parse::<f64>() {
+            Ok(v) if (v * factor).is_finite() => Num::In(v * factor),
+            _ => Num::None,
         }
     }
 
@@ -0,0 +1,63 @@
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+
+/// Encodes the inputs of the calculation as a query string like `u=12&i=2.5`. Outputs aren't
+/// encoded since they are calculated again when decoding.
+pub fn encode(calc: &Calculation) -> String {
+    Field::ALL.iter()
+        .filter_map(|f| match calc.get(*f) {
+            Num::In(v) => Some(format!("{}={}", f.id(), v)),
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// Decodes the inputs of a calculation from a query string with an optional leading '?' or '#'.
+/// Unknown keys and invalid values are ignored, so that links stay valid when fields are added.
+pub fn decode(s: &str) -> Calculation {
+    let mut calc = Calculation::new();
+
+    for pair in s.trim_start_matches(&['?', '#'][..]).split('&') {
+        let mut parts = pair.splitn(2, '=');
+        let id = parts.next().unwrap_or_default();
+        let value = parts.next().and_then(|v| v.parse::<f64>().ok());
+
+        if let (Some(f), Some(v)) = (Field::from_id(id), value) {
+            *calc.get_mut(f) = Num::In(v);
+        }
+    }
+
+    calc
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{Current, MotorEfficiency, TransmissionRatio, TransmissionSpeed, Voltage};
+    use crate::calc::number::Num;
+    use crate::calc::query::{decode, encode};
+
+    #[test]
+    fn test_query() {
+        let mut rng = rand::thread_rng();
+
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(rng.gen());
+        calc[MotorEfficiency] = Num::In(rng.gen());
+        calc[TransmissionRatio] = Num::In(1.0 / 14.0);
+        let calc = calc.fill_missing();
+
+        let query = encode(&calc);
+        assert_eq!(query.matches('=').count(), 3);
+        assert_eq!(decode(&query).fill_missing(), calc);
+
+        let calc = decode("#u=12&x_new=4&i=2,5&n_t=300");
+        assert_eq!(calc[Voltage], Num::In(12.0));
+        assert_eq!(calc[Current], Num::None);
+        assert_eq!(calc[TransmissionSpeed], Num::In(300.0));
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::newton::solve_linear;
+use crate::calc::number::Num;
+use crate::error::Error;
+use crate::error::ErrorKind::{Underconstrained, Unreachable};
+
+/// The tolerance of inputs without a given one relative to their value.
+pub const DEFAULT_RELATIVE_TOLERANCE: f64 = 0.01;
+/// The maximum number of Gauss-Newton iterations.
+const MAX_ITERATIONS: usize = 50;
+/// The relative step size below which the iteration is considered converged.
+const STEP_TOLERANCE: f64 = 1e-12;
+
+/// A structure holding the deviation of a measured input from its reconciled value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Residual {
+    pub field: Field,
+    pub measured: f64,
+    pub reconciled: f64,
+    /// The deviation relative to the tolerance of the input.
+    pub normalized: f64,
+}
+
+/// A structure holding the closest consistent calculation to the measured inputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reconciliation {
+    /// The calculation with the inputs replaced by their reconciled values.
+    pub calc: Calculation,
+    pub residuals: Vec<Residual>,
+    pub iterations: usize,
+}
+
+/// Finds the consistent calculation whose inputs deviate the least from the measured inputs in
+/// the least squares sense. The deviations are weighted by the tolerance of every input, inputs
+/// without a tolerance are assumed to be accurate to DEFAULT_RELATIVE_TOLERANCE.
+pub fn reconcile(calc: &Calculation, tolerances: &HashMap<Field, f64>) -> crate::error::Result<Reconciliation> {
+    let measured = Field::ALL.iter()
+        .filter_map(|f| match calc.get(*f) {
+            Num::In(v) => Some((*f, v)),
+            _ => None,
+        })
+        .collect::<Vec<(Field, f64)>>();
+    let sigma = |(f, v): &(Field, f64)| match tolerances.get(f) {
+        Some(t) if *t > 0.0 => *t,
+        _ => (v.abs() * DEFAULT_RELATIVE_TOLERANCE).max(f64::MIN_POSITIVE),
+    };
+    let sigmas = measured.iter().map(sigma).collect::<Vec<f64>>();
+
+    let basis = basis(calc, &measured);
+    let mut p = basis.iter().map(|i| measured[*i].1).collect::<Vec<f64>>();
+
+    // the values of all measured inputs if only the basis is given
+    let evaluate = |p: &[f64]| {
+        let mut c = Calculation::new();
+        for (i, v) in basis.iter().zip(p) {
+            *c.get_mut(measured[*i].0) = Num::In(*v);
+        }
+        let solved = c.fill_missing();
+
+        let values = measured.iter()
+            .map(|(f, _)| solved.get(*f).as_option())
+            .collect::<Option<Vec<f64>>>()?;
+        Some((solved, values))
+    };
+    let residuals = |p: &[f64]| {
+        evaluate(p).map(|(_, values)| {
+            values.iter().zip(&measured).zip(&sigmas).map(|((v, (_, m)), s)| (v - m) / s).collect::<Vec<f64>>()
+        })
+    };
+    let cost = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>();
+
+    let mut r = residuals(&p).ok_or_else(|| Error::new(Underconstrained, "The inputs don't determine the calculation"))?;
+    let mut iterations = 0;
+
+    while iterations < MAX_ITERATIONS && basis.len() < measured.len() {
+        iterations += 1;
+
+        let mut jacobian = vec![vec![0.0; p.len()]; r.len()];
+        for j in 0..p.len() {
+            let h = 1e-7 * p[j].abs().max(1e-3);
+            let mut shifted = p.clone();
+            shifted[j] += h;
+            let r_h = residuals(&shifted).ok_or_else(|| Error::new(Unreachable, "The calculation can't be solved near the inputs"))?;
+
+            for (i, row) in jacobian.iter_mut().enumerate() {
+                row[j] = (r_h[i] - r[i]) / h;
+            }
+        }
+
+        // normal equations jᵀj · step = -jᵀr
+        let jtj = (0..p.len())
+            .map(|a| (0..p.len()).map(|b| jacobian.iter().map(|row| row[a] * row[b]).sum()).collect())
+            .collect::<Vec<Vec<f64>>>();
+        let jtr = (0..p.len())
+            .map(|a| -jacobian.iter().zip(&r).map(|(row, r)| row[a] * r).sum::<f64>())
+            .collect::<Vec<f64>>();
+        let step = match solve_linear(jtj, jtr) {
+            Some(s) => s,
+            None => break,
+        };
+
+        let mut factor = 1.0;
+        let mut improved = false;
+        for _ in 0..20 {
+            let candidate = p.iter().zip(&step).map(|(p, s)| p + factor * s).collect::<Vec<f64>>();
+            if let Some(r_c) = residuals(&candidate) {
+                if cost(&r_c) <= cost(&r) {
+                    p = candidate;
+                    r = r_c;
+                    improved = true;
+                    break;
+                }
+            }
+            factor /= 2.0;
+        }
+
+        let converged = step.iter().zip(&p).all(|(s, p)| (factor * s).abs() <= STEP_TOLERANCE * p.abs().max(1.0));
+        if !improved || converged {
+            break;
+        }
+    }
+
+    let (solved, values) = evaluate(&p).ok_or_else(|| Error::new(Unreachable, "The calculation can't be solved near the inputs"))?;
+    let mut calc = solved;
+    for ((f, _), v) in measured.iter().zip(&values) {
+        *calc.get_mut(*f) = Num::In(*v);
+    }
+
+    let residuals = measured.iter()
+        .zip(&values)
+        .zip(&sigmas)
+        .map(|(((field, m), v), s)| Residual { field: *field, measured: *m, reconciled: *v, normalized: (m - v) / s })
+        .collect();
+
+    Ok(Reconciliation { calc, residuals, iterations })
+}
+
+/// Returns the indices of measured inputs that determine all others. Inputs that can be
+/// calculated from the remaining ones are left out one after another.
+fn basis(calc: &Calculation, measured: &[(Field, f64)]) -> Vec<usize> {
+    let mut basis = (0..measured.len()).collect::<Vec<usize>>();
+
+    for i in 0..measured.len() {
+        let mut c = Calculation::new();
+        for j in basis.iter().filter(|j| **j != i) {
+            *c.get_mut(measured[*j].0) = calc.get(measured[*j].0);
+        }
+
+        if c.fill_missing().get(measured[i].0).is_num() {
+            basis.retain(|j| *j != i);
+        }
+    }
+
+    basis
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{ArmatureResistance, Current, ElectricalMotorLoss, InputPower, Voltage};
+    use crate::calc::number::Num;
+    use crate::calc::reconcile::reconcile;
+
+    #[test]
+    fn test_reconcile() {
+        let mut rng = rand::thread_rng();
+        let u: f64 = rng.gen_range(6.0, 48.0);
+        let i: f64 = rng.gen_range(0.5, 10.0);
+
+        // the measured power is 2 % too high
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(u);
+        calc[Current] = Num::In(i);
+        calc[InputPower] = Num::In(u * i * 1.02);
+        calc[ArmatureResistance] = Num::In(0.5);
+        assert!(calc.fill_missing().check().is_err());
+
+        // with equal relative tolerances the error is split evenly in the logarithm
+        let result = reconcile(&calc, &HashMap::new()).unwrap();
+        let c = result.calc;
+        assert!((c[Voltage].num() * c[Current].num() - c[InputPower].num()).abs() < 1e-9 * c[InputPower].num());
+        assert!(c[Voltage].num() > u && c[Current].num() > i && c[InputPower].num() < u * i * 1.02);
+        assert_eq!(c[ArmatureResistance], Num::In(0.5));
+        assert!(c[ElectricalMotorLoss].is_output());
+        assert_eq!(result.residuals.len(), 4);
+        assert!(result.residuals.iter().any(|r| r.field == InputPower && r.normalized > 0.5));
+
+        // an accurate voltage and current leave the whole error to the power
+        let mut tolerances = HashMap::new();
+        tolerances.insert(Voltage, 1e-9);
+        tolerances.insert(Current, 1e-9);
+        let c = reconcile(&calc, &tolerances).unwrap().calc;
+        assert!((c[Voltage].num() - u).abs() < 1e-6 * u);
+        assert!((c[InputPower].num() - u * i).abs() < 1e-6 * u * i);
+
+        let mut consistent = calc;
+        consistent[InputPower] = Num::None;
+        let result = reconcile(&consistent, &HashMap::new()).unwrap();
+        assert!(result.residuals.iter().all(|r| r.normalized == 0.0));
+    }
+}
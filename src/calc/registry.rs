@@ -0,0 +1,209 @@
+use std::f64::consts::PI;
+
+use once_cell::sync::Lazy;
+
+use crate::calc::expression::{Expr, Relation};
+use crate::calc::field::Notation;
+
+use self::Field::*;
+
+/// A structure declaring how a quantity is identified, labeled and entered.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quantity {
+    pub field: Field,
+    /// The id used in files, links, CSV headers and on the command line, like `p_m_l_el`.
+    pub id: &'static str,
+    pub symbol: &'static str,
+    pub sub: &'static str,
+    pub unit: &'static str,
+    pub description: &'static str,
+    pub notation: Notation,
+    /// The column and row of the field in the grid both the web app and the terminal form place
+    /// the fields in.
+    pub position: (u16, u16),
+}
+
+/// A structure declaring the quantities of a physical domain and the relations between them.
+/// Relations may also use the quantities of modules declared before.
+#[derive(Copy, Clone, Debug)]
+pub struct Module {
+    pub name: &'static str,
+    /// The quantities in the order they are displayed.
+    pub quantities: &'static [Quantity],
+    pub relations: fn() -> Vec<Relation>,
+}
+
+/// Declares the modules and generates the Field enum with a variant for every quantity, in the
+/// order the quantities are declared.
+macro_rules! modules {
+    ($($module:ident {
+        name: $name:expr,
+        quantities: [$($field:ident: $notation:ident($($arg:expr),*)),* $(,)?],
+        relations: $relations:expr $(,)?
+    }),* $(,)?) => {
+        /// A enum representing the fields of a calculation in the order they are displayed. The
+        /// variants are generated from the quantities declared by the modules of the registry.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Field {
+            $($($field,)*)*
+        }
+
+        impl Field {
+            /// All fields in the order they are displayed.
+            pub const ALL: &'static [Field] = &[$($(Field::$field,)*)*];
+        }
+
+        $(const $module: Module = Module {
+            name: $name,
+            quantities: &[$($notation(Field::$field, $($arg),*)),*],
+            relations: $relations,
+        };)*
+
+        /// All modules in the order their quantities are displayed.
+        pub const MODULES: &[Module] = &[$($module),*];
+
+        /// The declarations of the quantities of all modules in the order of Field::ALL, so that
+        /// the declaration of a field is found at its index.
+        const QUANTITIES: &[Quantity] = &[$($($notation(Field::$field, $($arg),*),)*)*];
+    };
+}
+
+// The forms, the solver, the file formats and the command line interface are built from the
+// modules. Since the Field enum is generated from the quantities of all modules at once, every
+// module is declared in this single invocation and can't register itself from its own file, only
+// the function returning its relations may live elsewhere.
+modules! {
+    MOTOR {
+        name: "Motor",
+        quantities: [
+            InputPower: number("p_in", "P", "In", "W", "Input power", (0, 2)),
+            Voltage: number("u", "U", "", "V", "Voltage", (0, 3)),
+            Current: number("i", "I", "", "A", "Current", (0, 4)),
+            ArmatureResistance: number("r_a", "R", "A", "Ω", "Armature resistance", (0, 5)),
+            ElectricalMotorLoss: number("p_m_l_el", "P", "ML_el", "W", "Electrical motor power loss", (1, 0)),
+            MechanicalMotorLoss: number("p_m_l_mech", "P", "ML_mech", "W", "Mechanical motor power loss", (3, 0)),
+            MotorLoss: number("p_m_l", "P", "ML", "W", "Motor power loss", (2, 1)),
+            MotorPower: number("p_m", "P", "M", "W", "Motor power", (4, 2)),
+            MotorTorque: number("m_m", "M", "M", "Nm", "Motor torque", (4, 3)),
+            MotorSpeed: number("n_m", "n", "M", "rpm", "Motor speed", (4, 4)),
+            MotorEfficiency: number("eta_m", "η", "M", "%", "Motor efficiency", (2, 6)),
+        ],
+        relations: motor_relations,
+    },
+    TRANSMISSION {
+        name: "Transmission",
+        quantities: [
+            TransmissionLoss: number("p_t_l", "P", "TL", "W", "Transmission power loss", (6, 1)),
+            TransmissionPower: number("p_t", "P", "T", "W", "Transmission power", (8, 2)),
+            TransmissionTorque: number("m_t", "M", "T", "Nm", "Transmission torque", (8, 3)),
+            TransmissionSpeed: number("n_t", "n", "T", "rpm", "Transmission speed", (8, 4)),
+            TransmissionRatio: ratio("i_t", "i", "", "", "Transmission ratio", (6, 5)),
+            TransmissionEfficiency: number("eta_t", "η", "T", "%", "Transmission efficiency", (6, 6)),
+        ],
+        relations: transmission_relations,
+    },
+}
+
+/// Returns the declaration of a quantity entered as a number.
+const fn number(
+    field: Field,
+    id: &'static str,
+    symbol: &'static str,
+    sub: &'static str,
+    unit: &'static str,
+    description: &'static str,
+    position: (u16, u16),
+) -> Quantity {
+    Quantity { field, id, symbol, sub, unit, description, notation: Notation::Number, position }
+}
+
+/// Returns the declaration of a quantity entered as a ratio.
+const fn ratio(
+    field: Field,
+    id: &'static str,
+    symbol: &'static str,
+    sub: &'static str,
+    unit: &'static str,
+    description: &'static str,
+    position: (u16, u16),
+) -> Quantity {
+    Quantity { field, id, symbol, sub, unit, description, notation: Notation::Ratio, position }
+}
+
+/// The factor converting a speed in rpm to an angular velocity in rad/s.
+fn rpm() -> Expr {
+    Expr::constant(2.0 * PI / 60.0)
+}
+
+fn percent() -> Expr {
+    Expr::constant(0.01)
+}
+
+fn motor_relations() -> Vec<Relation> {
+    let var = Expr::var;
+
+    vec![
+        Relation::new(Expr::product(vec![var(Voltage), var(Current)]), var(InputPower)),
+        Relation::new(Expr::sum(vec![var(MotorLoss), var(MotorPower)]), var(InputPower)),
+        Relation::new(Expr::sum(vec![var(ElectricalMotorLoss), var(MechanicalMotorLoss)]), var(MotorLoss)),
+        Relation::new(Expr::product(vec![Expr::pow(var(Current), 2.0), var(ArmatureResistance)]), var(ElectricalMotorLoss)),
+        Relation::new(Expr::product(vec![var(InputPower), var(MotorEfficiency), percent()]), var(MotorPower)),
+        Relation::new(Expr::product(vec![var(MotorSpeed), rpm(), var(MotorTorque)]), var(MotorPower)),
+    ]
+}
+
+fn transmission_relations() -> Vec<Relation> {
+    let var = Expr::var;
+
+    vec![
+        Relation::new(Expr::sum(vec![var(TransmissionPower), var(TransmissionLoss)]), var(MotorPower)),
+        Relation::new(Expr::product(vec![var(MotorSpeed), var(TransmissionRatio)]), var(TransmissionSpeed)),
+        Relation::new(Expr::product(vec![var(MotorPower), var(TransmissionEfficiency), percent()]), var(TransmissionPower)),
+        Relation::new(Expr::product(vec![var(TransmissionSpeed), rpm(), var(TransmissionTorque)]), var(TransmissionPower)),
+    ]
+}
+
+/// The relations of all modules, built once since every solve needs them.
+static RELATIONS: Lazy<Vec<Relation>> = Lazy::new(|| MODULES.iter().flat_map(|m| (m.relations)()).collect());
+
+/// Returns the declarations of the quantities of all modules in the order they are displayed.
+pub fn quantities() -> impl Iterator<Item = &'static Quantity> {
+    QUANTITIES.iter()
+}
+
+/// Returns the declaration of the quantity of the field.
+pub fn quantity_of(field: Field) -> &'static Quantity {
+    &QUANTITIES[field.index()]
+}
+
+/// Returns the relations of all modules.
+pub fn relations() -> &'static [Relation] {
+    &RELATIONS
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::field::Field;
+    use crate::calc::registry::{quantities, relations, MODULES};
+
+    #[test]
+    fn test_registry() {
+        // every field is declared exactly once, in the order of Field::ALL and by its module
+        let declared = quantities().map(|q| q.field).collect::<Vec<Field>>();
+        assert_eq!(declared, Field::ALL.to_vec());
+        assert!(MODULES.iter().flat_map(|m| m.quantities).eq(quantities()));
+
+        // the ids and positions are unique
+        assert!(quantities().all(|a| quantities().filter(|b| a.id == b.id).count() == 1));
+        assert!(quantities().all(|a| quantities().filter(|b| a.position == b.position).count() == 1));
+
+        // the relations of a module only use its own quantities and those of modules before
+        for (i, module) in MODULES.iter().enumerate() {
+            let known = MODULES[..=i].iter().flat_map(|m| m.quantities).map(|q| q.field).collect::<Vec<Field>>();
+            assert!((module.relations)().iter().flat_map(|r| r.variables()).all(|f| known.contains(&f)));
+        }
+
+        // every field can be calculated from others
+        assert!(Field::ALL.iter().all(|f| relations().iter().any(|r| r.variables().contains(f))));
+    }
+}
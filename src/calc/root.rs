@@ -0,0 +1,82 @@
+/// The maximum number of bisections.
+const MAX_ITERATIONS: usize = 200;
+/// The number of values sampled on each side of the start when searching for a bracket.
+const BRACKET_SAMPLES: i32 = 60;
+/// The factor between two sampled values when searching for a bracket.
+const BRACKET_FACTOR: f64 = 1.2;
+
+/// Returns the closest values around the start between which the function changes its sign. The
+/// values are sampled geometrically, so they keep the sign of the start.
+pub fn bracket(f: impl Fn(f64) -> Option<f64>, start: f64) -> Option<(f64, f64)> {
+    let start = if start == 0.0 { 1.0 } else { start };
+    let value = |k: i32| start * BRACKET_FACTOR.powi(k);
+    let sign = |k: i32| f(value(k)).map(f64::signum);
+
+    for k in 0..BRACKET_SAMPLES {
+        for (a, b) in [(k, k + 1), (-k - 1, -k)].iter().copied() {
+            if let (Some(s_a), Some(s_b)) = (sign(a), sign(b)) {
+                if s_a != s_b || s_a == 0.0 {
+                    return Some((value(a).min(value(b)), value(a).max(value(b))));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the root of the function between the bounds found by bisection or None if the
+/// function doesn't change its sign between them or can't be evaluated.
+pub fn bisect(f: impl Fn(f64) -> Option<f64>, lower: f64, upper: f64) -> Option<f64> {
+    let (mut a, mut b) = (lower, upper);
+    let (mut f_a, f_b) = (f(a)?, f(b)?);
+
+    if f_a == 0.0 {
+        return Some(a);
+    }
+    if f_b == 0.0 {
+        return Some(b);
+    }
+    if f_a.signum() == f_b.signum() {
+        return None;
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let m = (a + b) / 2.0;
+        if m == a || m == b {
+            break;
+        }
+
+        let f_m = f(m)?;
+        if f_m == 0.0 {
+            return Some(m);
+        }
+        if f_m.signum() == f_a.signum() {
+            a = m;
+            f_a = f_m;
+        } else {
+            b = m;
+        }
+    }
+
+    Some((a + b) / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::root::{bisect, bracket};
+
+    #[test]
+    fn test_root() {
+        let f = |x: f64| Some(x * x - 2.0);
+
+        let (a, b) = bracket(f, 10.0).unwrap();
+        assert!(a < 2_f64.sqrt() && b > 2_f64.sqrt());
+        assert!((bisect(f, a, b).unwrap() - 2_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(bracket(f, -1.0).map(|(a, _)| a < 0.0), Some(true));
+
+        assert_eq!(bisect(f, 2.0, 3.0), None);
+        assert_eq!(bisect(|x| if x < 1.0 { Some(x) } else { None }, -1.0, 2.0), None);
+        assert_eq!(bracket(|x| Some(x * x + 1.0), 1.0), None);
+    }
+}
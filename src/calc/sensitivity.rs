@@ -0,0 +1,111 @@
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+
+/// The step of the finite differences relative to the value of the input.
+const RELATIVE_STEP: f64 = 1e-6;
+
+/// A structure holding the sensitivities of every output of a calculation with respect to every
+/// input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix {
+    pub inputs: Vec<Field>,
+    pub outputs: Vec<Field>,
+    /// The partial derivatives of the outputs with respect to the inputs, a row for every output.
+    pub derivatives: Vec<Vec<Option<f64>>>,
+}
+
+impl Matrix {
+    /// Returns the partial derivative of the output with respect to the input or None if it
+    /// couldn't be calculated.
+    pub fn derivative(&self, output: Field, input: Field) -> Option<f64> {
+        let row = self.outputs.iter().position(|o| *o == output)?;
+        let column = self.inputs.iter().position(|i| *i == input)?;
+
+        self.derivatives[row][column]
+    }
+
+    /// Returns the relative change of the output caused by a relative change of the input. An
+    /// elasticity of 1 means that the output changes by 1 % if the input changes by 1 %.
+    pub fn elasticity(&self, calc: &Calculation, output: Field, input: Field) -> Option<f64> {
+        let x = calc.get(input).as_option()?;
+        let y = calc.get(output).as_option().filter(|y| *y != 0.0)?;
+
+        Some(self.derivative(output, input)? * x / y)
+    }
+}
+
+/// Calculates the partial derivatives of every output of the calculation with respect to every
+/// input by central finite differences.
+pub fn sensitivities(calc: &Calculation) -> Matrix {
+    let base = calc.fill_missing();
+    let fields = |f: fn(&Num) -> bool| Field::ALL.iter()
+        .copied()
+        .filter(|field| f(&base.get(*field)))
+        .collect::<Vec<Field>>();
+    let inputs = fields(Num::is_input);
+    let outputs = fields(Num::is_output);
+
+    let mut derivatives = vec![vec![None; inputs.len()]; outputs.len()];
+
+    for (column, input) in inputs.iter().enumerate() {
+        let x = base.get(*input).as_option().unwrap_or_default();
+        let h = if x == 0.0 { RELATIVE_STEP } else { x.abs() * RELATIVE_STEP };
+
+        let solve = |v: f64| {
+            let mut c = base;
+            *c.get_mut(*input) = Num::In(v);
+            c.fill_missing()
+        };
+        let (lower, upper) = (solve(x - h), solve(x + h));
+
+        for (row, output) in outputs.iter().enumerate() {
+            let value = |c: &Calculation| c.get(*output).as_option();
+
+            if let (Some(a), Some(b)) = (value(&lower), value(&upper)) {
+                derivatives[row][column] = Some((b - a) / (2.0 * h));
+            }
+        }
+    }
+
+    Matrix { inputs, outputs, derivatives }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::*;
+    use crate::calc::number::Num;
+    use crate::calc::sensitivity::sensitivities;
+
+    fn assert_close(a: Option<f64>, b: f64) {
+        assert!((a.unwrap() - b).abs() < 1e-4 * b.abs().max(1.0), "{:?} != {}", a, b);
+    }
+
+    #[test]
+    fn test_sensitivities() {
+        let mut rng = rand::thread_rng();
+        let u: f64 = rng.gen_range(1.0, 50.0);
+        let i: f64 = rng.gen_range(0.1, 10.0);
+
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(u);
+        calc[Current] = Num::In(i);
+        calc[ArmatureResistance] = Num::In(0.5);
+        let calc = calc.fill_missing();
+
+        let matrix = sensitivities(&calc);
+        assert_eq!(matrix.inputs, vec![Voltage, Current, ArmatureResistance]);
+        assert!(matrix.outputs.contains(&InputPower));
+
+        assert_close(matrix.derivative(InputPower, Voltage), i);
+        assert_close(matrix.derivative(InputPower, ArmatureResistance), 0.0);
+        assert_close(matrix.derivative(ElectricalMotorLoss, Current), 2.0 * i * 0.5);
+        assert_close(matrix.elasticity(&calc, InputPower, Voltage), 1.0);
+        assert_close(matrix.elasticity(&calc, ElectricalMotorLoss, Current), 2.0);
+        assert_eq!(matrix.derivative(MotorTorque, Voltage), None);
+        assert_eq!(matrix.derivative(Voltage, Current), None);
+    }
+}
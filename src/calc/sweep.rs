@@ -0,0 +1,169 @@
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::number::Num;
+use crate::error::Error;
+use crate::error::ErrorKind::InvalidInput;
+
+/// A structure describing the values an input field is varied over.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sweep {
+    /// The varied field.
+    pub field: Field,
+    pub from: f64,
+    pub to: f64,
+    /// The number of values including the first and the last one.
+    pub steps: usize,
+}
+
+impl Sweep {
+    pub fn new(field: Field, from: f64, to: f64, steps: usize) -> Self {
+        Self { field, from, to, steps }
+    }
+
+    /// Returns the evenly spaced values from the start to the end of the range.
+    pub fn values(&self) -> Vec<f64> {
+        match self.steps {
+            0 => Vec::new(),
+            1 => vec![self.from],
+            n => (0..n)
+                .map(|i| self.from + (self.to - self.from) * i as f64 / (n - 1) as f64)
+                .collect(),
+        }
+    }
+
+    /// Solves the calculation once for every value of the varied field. The field is set as an
+    /// input, all other inputs are kept.
+    pub fn run(&self, calc: &Calculation) -> Vec<Calculation> {
+        self.values()
+            .into_iter()
+            .map(|v| {
+                let mut c = calc.clear_output();
+                *c.get_mut(self.field) = Num::In(v);
+                c.fill_missing()
+            })
+            .collect()
+    }
+}
+
+/// A structure describing the values two input fields are varied over simultaneously, like the
+/// torque and the voltage of an efficiency map.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Grid {
+    pub x: Sweep,
+    pub y: Sweep,
+}
+
+impl Grid {
+    pub fn new(x: Sweep, y: Sweep) -> Self {
+        Self { x, y }
+    }
+
+    /// Solves the calculation once for every combination of values. Returns a row for every
+    /// value of y holding the calculations for every value of x.
+    pub fn run(&self, calc: &Calculation) -> crate::error::Result<Vec<Vec<Calculation>>> {
+        if self.x.field == self.y.field {
+            return Err(Error::new(InvalidInput, "The same field can't be varied twice"));
+        }
+
+        Ok(self.y.values()
+            .into_iter()
+            .map(|v| {
+                let mut c = *calc;
+                *c.get_mut(self.y.field) = Num::In(v);
+                self.x.run(&c)
+            })
+            .collect())
+    }
+
+    /// Formats the values of the field as a CSV grid. The first row holds the values
+    /// of x, the first column the values of y and missing values are left empty.
+    pub fn to_csv(&self, results: &[Vec<Calculation>], field: Field) -> String {
+        let mut out = format!("{}\\{}", self.y.field.id(), self.x.field.id());
+        for x in self.x.values() {
+            out.push_str(&format!(",{}", x));
+        }
+        out.push('\n');
+
+        for (y, row) in self.y.values().iter().zip(results) {
+            out.push_str(&y.to_string());
+            for v in column(row, field) {
+                out.push(',');
+                if let Some(v) = v {
+                    out.push_str(&v.to_string());
+                }
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// Returns the values of the field of every solved calculation or None where it couldn't be
+/// calculated.
+pub fn column(results: &[Calculation], field: Field) -> Vec<Option<f64>> {
+    results.iter()
+        .map(|c| c.get(field).as_option())
+        .collect()
+}
+
+/// Returns a table with a row for every solved calculation containing the values of the fields.
+pub fn table(results: &[Calculation], fields: &[Field]) -> Vec<Vec<Option<f64>>> {
+    results.iter()
+        .map(|c| fields.iter().map(|f| c.get(*f).as_option()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{ArmatureResistance, Current, ElectricalMotorLoss, InputPower, MotorTorque, Voltage};
+    use crate::calc::number::Num;
+    use crate::calc::sweep::{column, table, Grid, Sweep};
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_values() {
+        assert_eq!(Sweep::new(Voltage, 6.0, 24.0, 10).values(), vec![6.0, 8.0, 10.0, 12.0, 14.0, 16.0, 18.0, 20.0, 22.0, 24.0]);
+        assert_eq!(Sweep::new(Voltage, 6.0, 24.0, 1).values(), vec![6.0]);
+        assert!(Sweep::new(Voltage, 6.0, 24.0, 0).values().is_empty());
+    }
+
+    #[test]
+    fn test_run() {
+        let mut rng = rand::thread_rng();
+        let i: f64 = rng.gen_range(0.1, 10.0);
+
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::Out(3.0);
+        calc[Current] = Num::In(i);
+
+        let results = Sweep::new(Voltage, 6.0, 24.0, 4).run(&calc);
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|c| c[Voltage].is_input()));
+        assert_eq!(column(&results, InputPower), vec![Some(6.0 * i), Some(12.0 * i), Some(18.0 * i), Some(24.0 * i)]);
+        assert_eq!(table(&results, &[Voltage, ArmatureResistance])[1], vec![Some(12.0), None]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut calc = Calculation::new();
+        calc[ArmatureResistance] = Num::In(0.5);
+
+        let grid = Grid::new(Sweep::new(Current, 1.0, 2.0, 2), Sweep::new(Voltage, 6.0, 24.0, 3));
+        let results = grid.run(&calc).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(column(&results[2], InputPower), vec![Some(24.0), Some(48.0)]);
+        assert_eq!(results[0][1][ElectricalMotorLoss], Num::Out(2.0));
+
+        assert_eq!(grid.to_csv(&results, InputPower), "u\\i,1,2\n6,6,12\n15,15,30\n24,24,48\n");
+        assert_eq!(grid.to_csv(&results, MotorTorque), "u\\i,1,2\n6,,\n15,,\n24,,\n");
+
+        match Grid::new(Sweep::new(Voltage, 0.0, 1.0, 2), Sweep::new(Voltage, 0.0, 1.0, 2)).run(&calc).unwrap_err().kind {
+            ErrorKind::InvalidInput => (),
+            _ => panic!("Expected Error with ErrorKind InvalidInput"),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field::{self, *};
+
+/// A enum representing how severe a violated constraint is.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Severity {
+    /// The value is possible but unusual, like a motor efficiency above 95 %.
+    Warning,
+    /// The value is physically impossible, like a negative resistance.
+    Error,
+}
+
+/// A structure describing a violated constraint of a field.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// The field the issue is shown next to.
+    pub field: Field,
+    pub severity: Severity,
+    pub message: &'static str,
+}
+
+/// A structure describing a constraint on the value of a single field.
+struct Rule {
+    field: Field,
+    /// Returns true if the value satisfies the constraint, false otherwise.
+    valid: fn(f64) -> bool,
+    severity: Severity,
+    message: &'static str,
+}
+
+/// The constraints on the values of single fields.
+const RULES: [Rule; 13] = [
+    Rule { field: ArmatureResistance, valid: |v| v >= 0.0, severity: Severity::Error, message: "The resistance can't be negative" },
+    Rule { field: ArmatureResistance, valid: |v| v != 0.0, severity: Severity::Warning, message: "A resistance of zero means there are no electrical losses" },
+    Rule { field: ElectricalMotorLoss, valid: |v| v >= 0.0, severity: Severity::Error, message: "Losses can't be negative" },
+    Rule { field: MechanicalMotorLoss, valid: |v| v >= 0.0, severity: Severity::Error, message: "Losses can't be negative" },
+    Rule { field: MotorLoss, valid: |v| v >= 0.0, severity: Severity::Error, message: "Losses can't be negative" },
+    Rule { field: TransmissionLoss, valid: |v| v >= 0.0, severity: Severity::Error, message: "Losses can't be negative" },
+    Rule { field: MotorSpeed, valid: |v| v >= 0.0, severity: Severity::Error, message: "The speed can't be negative" },
+    Rule { field: TransmissionSpeed, valid: |v| v >= 0.0, severity: Severity::Error, message: "The speed can't be negative" },
+    Rule { field: TransmissionRatio, valid: |v| v > 0.0, severity: Severity::Error, message: "The transmission ratio has to be positive" },
+    Rule { field: MotorEfficiency, valid: |v| v > 0.0 && v <= 100.0, severity: Severity::Error, message: "The efficiency has to be above 0 % and at most 100 %" },
+    Rule { field: MotorEfficiency, valid: |v| v <= 95.0, severity: Severity::Warning, message: "Motor efficiencies above 95 % are unusual" },
+    Rule { field: TransmissionEfficiency, valid: |v| v > 0.0 && v <= 100.0, severity: Severity::Error, message: "The efficiency has to be above 0 % and at most 100 %" },
+    Rule { field: Voltage, valid: |v| v >= 0.0, severity: Severity::Warning, message: "A negative voltage reverses the motor" },
+];
+
+/// Returns the violated constraints of all fields that have a value. Besides the constraints of
+/// single fields the losses are checked against the power they are lost from.
+pub fn validate(calc: &Calculation) -> Vec<Issue> {
+    let value = |f: Field| calc.get(f).as_option();
+
+    let mut issues = RULES.iter()
+        .filter(|r| matches!(value(r.field), Some(v) if !(r.valid)(v)))
+        .map(|r| Issue { field: r.field, severity: r.severity, message: r.message })
+        .collect::<Vec<Issue>>();
+
+    let mut exceeds = |loss: Field, power: Field, message: &'static str| {
+        if let (Some(l), Some(p)) = (value(loss), value(power)) {
+            if l > p {
+                issues.push(Issue { field: loss, severity: Severity::Error, message });
+            }
+        }
+    };
+    exceeds(MotorLoss, InputPower, "The motor losses exceed the input power");
+    exceeds(MechanicalMotorLoss, InputPower, "The mechanical losses exceed the input power");
+    exceeds(TransmissionLoss, MotorPower, "The transmission losses exceed the motor power");
+
+    issues
+}
+
+/// Returns the issues of the field.
+pub fn issues_of(issues: &[Issue], field: Field) -> Vec<Issue> {
+    issues.iter().filter(|i| i.field == field).copied().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::calc::calculation::Calculation;
+    use crate::calc::field::Field::{ArmatureResistance, Current, MotorEfficiency, MotorLoss, TransmissionEfficiency, Voltage};
+    use crate::calc::number::Num;
+    use crate::calc::validation::{issues_of, validate, Severity};
+
+    #[test]
+    fn test_validate() {
+        let mut rng = rand::thread_rng();
+        let u: f64 = rng.gen_range(6.0, 48.0);
+        let i: f64 = rng.gen_range(0.5, 10.0);
+
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(u);
+        calc[Current] = Num::In(i);
+        calc[MotorEfficiency] = Num::In(80.0);
+        assert!(validate(&calc.fill_missing()).is_empty());
+
+        calc[ArmatureResistance] = Num::In(-0.5);
+        calc[TransmissionEfficiency] = Num::In(120.0);
+        let issues = validate(&calc);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+        assert_eq!(issues_of(&issues, TransmissionEfficiency).len(), 1);
+
+        // the losses follow from the inputs but exceed the input power
+        let mut calc = Calculation::new();
+        calc[Voltage] = Num::In(u);
+        calc[Current] = Num::In(i);
+        calc[MotorLoss] = Num::In(2.0 * u * i);
+        let issues = validate(&calc.fill_missing());
+        assert!(issues.iter().any(|i| i.field == MotorLoss && i.severity == Severity::Error));
+        assert!(issues.iter().any(|i| i.field == MotorEfficiency && i.severity == Severity::Error));
+
+        calc[MotorLoss] = Num::None;
+        calc[MotorEfficiency] = Num::In(97.0);
+        let issues = validate(&calc);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+    }
+}
@@ -0,0 +1,17 @@
+/// A structure describing a gearbox as listed in a manufacturer's catalogue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gearbox {
+    pub name: String,
+    /// Transmission ratio: i = n<sub>T</sub> / n<sub>M</sub>
+    pub i_t: f64,
+    /// Transmission efficiency: η<sub>T</sub> [%]
+    pub eta_t: f64,
+    /// Maximum continuous output torque: M<sub>max</sub> [Nm]
+    pub m_max: f64,
+    /// Mass: m [kg]
+    pub mass: f64,
+    /// Cost
+    pub cost: f64,
+    /// Length: l [m]
+    pub length: f64,
+}
@@ -0,0 +1,312 @@
+use std::io::Read;
+
+use serde_json::Value;
+
+use crate::calc::number::Num;
+use crate::catalogue::gearbox::Gearbox;
+use crate::catalogue::motor::Motor;
+
+/// A record of a manufacturer's datasheet table that can be imported.
+pub trait Record: Sized {
+    /// The key of the array holding the records inside a JSON catalogue.
+    const KEY: &'static str;
+    /// The ids and units of the numeric fields.
+    const FIELDS: &'static [(&'static str, &'static str)];
+
+    /// Creates the record from its name and the values in the order of the fields.
+    fn from_values(name: String, values: &[f64]) -> Self;
+}
+
+impl Record for Motor {
+    const KEY: &'static str = "motors";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("u", "V"),
+        ("r_a", "Ω"),
+        ("k_m", "Nm/A"),
+        ("i_0", "A"),
+        ("j", "kgm²"),
+        ("m_max", "Nm"),
+        ("n_max", "rpm"),
+        ("mass", "kg"),
+        ("cost", ""),
+        ("length", "m"),
+        ("diameter", "m"),
+    ];
+
+    fn from_values(name: String, values: &[f64]) -> Self {
+        Motor {
+            name,
+            u: values[0],
+            r_a: values[1],
+            k_m: values[2],
+            i_0: values[3],
+            j: values[4],
+            m_max: values[5],
+            n_max: values[6],
+            mass: values[7],
+            cost: values[8],
+            length: values[9],
+            diameter: values[10],
+        }
+    }
+}
+
+impl Record for Gearbox {
+    const KEY: &'static str = "gearboxes";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("i_t", ""),
+        ("eta_t", "%"),
+        ("m_max", "Nm"),
+        ("mass", "kg"),
+        ("cost", ""),
+        ("length", "m"),
+    ];
+
+    fn from_values(name: String, values: &[f64]) -> Self {
+        Gearbox {
+            name,
+            i_t: values[0],
+            eta_t: values[1],
+            m_max: values[2],
+            mass: values[3],
+            cost: values[4],
+            length: values[5],
+        }
+    }
+}
+
+/// Maps the fields of a record to the columns of a CSV table or the keys of a JSON object. Fields
+/// that aren't mapped are expected in a column named like the field itself.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnMapping {
+    columns: Vec<(String, String)>,
+}
+
+impl ColumnMapping {
+    /// Creates a new mapping that expects every field in a column of the same name.
+    pub fn new() -> ColumnMapping {
+        ColumnMapping { columns: Vec::new() }
+    }
+
+    /// Maps the field to the column.
+    pub fn column(mut self, field: impl Into<String>, column: impl Into<String>) -> Self {
+        let field = field.into();
+        self.columns.retain(|(f, _)| *f != field);
+        self.columns.push((field, column.into()));
+        self
+    }
+
+    /// Returns the name of the column the field is mapped to.
+    pub fn get<'a>(&'a self, field: &'a str) -> &'a str {
+        self.columns.iter()
+            .find(|(f, _)| f == field)
+            .map(|(_, c)| c.as_str())
+            .unwrap_or(field)
+    }
+}
+
+/// A structure representing an error in a single row of a table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowError {
+    /// The 1-based row. For CSV tables this is the line including the header.
+    pub row: usize,
+    pub column: String,
+    pub message: String,
+}
+
+impl RowError {
+    pub fn new(row: usize, column: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { row, column: column.into(), message: message.into() }
+    }
+}
+
+/// The records that could be imported and the errors of the rows that were skipped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Import<T> {
+    pub records: Vec<T>,
+    pub errors: Vec<RowError>,
+}
+
+impl<T> Import<T> {
+    fn new() -> Self {
+        Import { records: Vec::new(), errors: Vec::new() }
+    }
+
+    fn push(&mut self, result: Result<T, Vec<RowError>>) {
+        match result {
+            Ok(r) => self.records.push(r),
+            Err(mut e) => self.errors.append(&mut e),
+        }
+    }
+}
+
+/// Reads the records from a CSV table with a header row. Only failing to read the table as a
+/// whole is returned as an error, errors in single rows are collected in the import.
+pub fn from_csv<T: Record>(reader: impl Read, mapping: &ColumnMapping) -> csv::Result<Import<T>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let mut import = Import::new();
+
+    for result in reader.records() {
+        match result {
+            Ok(record) => {
+                let row = record.position().map_or(0, |p| p.line() as usize);
+                import.push(parse_record(row, mapping, |column| {
+                    headers.iter()
+                        .position(|h| h.trim() == column)
+                        .and_then(|i| record.get(i))
+                        .map(|v| v.to_string())
+                }));
+            }
+            Err(e) if e.is_io_error() => return Err(e),
+            Err(e) => {
+                let row = e.position().map_or(0, |p| p.line() as usize);
+                import.errors.push(RowError::new(row, "", e.to_string()));
+            }
+        }
+    }
+
+    Ok(import)
+}
+
+/// Reads the records from a JSON catalogue. The document is either an array of objects or an
+/// object holding such an array under the key of the record, so that motors and gearboxes can
+/// share one file:
+///
+/// ```json
+/// {
+///     "motors": [{ "name": "RE 25", "u": "24 V", "r_a": "2.18 Ω", "k_m": "30.2 mNm/A", ... }],
+///     "gearboxes": [{ "name": "GP 32", "i_t": "14:1", "eta_t": 80, ... }]
+/// }
+/// ```
+///
+/// Values are either numbers in the base unit or strings that are parsed like user input, with
+/// an optional trailing unit.
+pub fn from_json<T: Record>(json: &str, mapping: &ColumnMapping) -> serde_json::Result<Import<T>> {
+    let document: Value = serde_json::from_str(json)?;
+    let mut import = Import::new();
+
+    let rows = match document.get(T::KEY).unwrap_or(&document) {
+        Value::Array(rows) => rows,
+        _ => {
+            import.errors.push(RowError::new(0, T::KEY, "Expected an array of records"));
+            return Ok(import);
+        }
+    };
+
+    for (i, row) in rows.iter().enumerate() {
+        import.push(parse_record(i + 1, mapping, |column| {
+            match row.get(column)? {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        }));
+    }
+
+    Ok(import)
+}
+
+/// Parses a single record using the function to look up the raw value of a column.
+fn parse_record<T: Record>(row: usize, mapping: &ColumnMapping, get: impl Fn(&str) -> Option<String>) -> Result<T, Vec<RowError>> {
+    let mut errors = Vec::new();
+    let mut values = Vec::with_capacity(T::FIELDS.len());
+
+    let name_column = mapping.get("name");
+    let name = get(name_column).map(|n| n.trim().to_string()).unwrap_or_default();
+    if name.is_empty() {
+        errors.push(RowError::new(row, name_column, "Missing name"));
+    }
+
+    for (field, unit) in T::FIELDS {
+        let column = mapping.get(field);
+
+        match get(column) {
+            Some(s) => match parse_value(&s, unit) {
+                Some(v) if v < 0.0 => errors.push(RowError::new(row, column, "Value must not be negative")),
+                Some(v) => values.push(v),
+                None if unit.is_empty() => errors.push(RowError::new(row, column, format!("Invalid value '{}'", s))),
+                None => errors.push(RowError::new(row, column, format!("Invalid value '{}', expected [{}]", s, unit))),
+            },
+            None => errors.push(RowError::new(row, column, "Missing value")),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(T::from_values(name, &values))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses a value with an optional metric prefix and an optional trailing unit. Values without a
+/// unit may be given as a ratio. A unit that is also a metric prefix, like `m`, is only read as
+/// unit if it is separated by a space or follows a prefix, so `54.2m` and `54.2mm` are both 54.2 mm
+/// while `54.2 m` is 54.2 m.
+fn parse_value(s: &str, unit: &str) -> Option<f64> {
+    let s = s.trim();
+
+    let num = match s.strip_suffix(unit).filter(|_| !unit.is_empty()) {
+        Some(value) if value.ends_with(' ') => Num::parse(value.replace(' ', "")),
+        Some(value) => match Num::parse(s.replace(' ', "")) {
+            Num::None => Num::parse(value.replace(' ', "")),
+            num => num,
+        },
+        None if unit.is_empty() && s.contains(':') => Num::parse_ratio(s.replace(' ', "")),
+        None => Num::parse(s.replace(' ', "")),
+    };
+
+    num.as_option().filter(|v| v.is_finite())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::catalogue::gearbox::Gearbox;
+    use crate::catalogue::import::{ColumnMapping, from_csv, from_json};
+    use crate::catalogue::motor::Motor;
+
+    #[test]
+    fn test_motors_from_csv() {
+        let csv = "\
+Type,Nominal voltage,r_a,k_m,i_0,j,m_max,n_max,mass,cost,length,diameter
+RE 25,24 V,2.18 Ω,30.2m,34.5mA,1.08µ,28.8m,14000,130m,120,54.2m,25m
+RE 30,48,x,51.9m,16.9m,3.33µ,85m,12000,238m,180,68m,30m
+";
+        let mapping = ColumnMapping::new()
+            .column("name", "Type")
+            .column("u", "Nominal voltage");
+        let import = from_csv::<Motor>(csv.as_bytes(), &mapping).unwrap();
+
+        assert_eq!(import.records.len(), 1);
+        let motor = &import.records[0];
+        assert_eq!(motor.name, "RE 25");
+        assert_eq!(motor.u, 24.0);
+        assert_eq!(motor.r_a, 2.18);
+        assert!((motor.k_m - 0.0302).abs() < 1e-12);
+        assert!((motor.length - 0.0542).abs() < 1e-12);
+        assert!((motor.diameter - 0.025).abs() < 1e-12);
+
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(import.errors[0].row, 3);
+        assert_eq!(import.errors[0].column, "r_a");
+    }
+
+    #[test]
+    fn test_gearboxes_from_json() {
+        let json = r#"{
+            "gearboxes": [
+                { "name": "GP 32", "i_t": "14:1", "eta_t": "80 %", "m_max": 0.75, "mass": 0.118, "cost": 90, "length": 0.0364 },
+                { "name": "GP 42", "i_t": "3.5:1", "eta_t": 90, "mass": 0.26, "cost": 110, "length": 0.0406 }
+            ]
+        }"#;
+        let import = from_json::<Gearbox>(json, &ColumnMapping::new()).unwrap();
+
+        assert_eq!(import.records.len(), 1);
+        assert_eq!(import.records[0].i_t, 1.0 / 14.0);
+        assert_eq!(import.records[0].eta_t, 80.0);
+
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(import.errors[0].row, 2);
+        assert_eq!(import.errors[0].column, "m_max");
+    }
+}
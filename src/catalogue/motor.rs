@@ -0,0 +1,40 @@
+use std::f64::consts::PI;
+
+/// A structure describing a DC motor as listed in a manufacturer's catalogue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Motor {
+    pub name: String,
+    /// Nominal voltage: U [V]
+    pub u: f64,
+    /// Armature resistance: R<sub>A</sub> [Ω]
+    pub r_a: f64,
+    /// Torque constant: k<sub>M</sub> [Nm/A]
+    pub k_m: f64,
+    /// No load current: I<sub>0</sub> [A]
+    pub i_0: f64,
+    /// Rotor inertia: J<sub>M</sub> [kgm²]
+    pub j: f64,
+    /// Maximum continuous torque: M<sub>max</sub> [Nm]
+    pub m_max: f64,
+    /// Maximum speed: n<sub>max</sub> [rpm]
+    pub n_max: f64,
+    /// Mass: m [kg]
+    pub mass: f64,
+    /// Cost
+    pub cost: f64,
+    /// Length: l [m]
+    pub length: f64,
+    /// Diameter: d [m]
+    pub diameter: f64,
+}
+
+impl Motor {
+    /// Returns the current and the voltage needed to deliver the torque [Nm] at the speed [rpm].
+    /// The no load current accounts for the friction inside the motor.
+    pub fn operating_point(&self, m_m: f64, n_m: f64) -> (f64, f64) {
+        let i = m_m / self.k_m + self.i_0;
+        let u = i * self.r_a + self.k_m * n_m * (2.0 * PI / 60.0);
+
+        (i, u)
+    }
+}
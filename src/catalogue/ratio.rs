@@ -0,0 +1,166 @@
+use crate::calc::number::Num;
+use crate::catalogue::motor::Motor;
+
+/// The range of transmission ratios that is searched for the optimum.
+const RANGE: (f64, f64) = (0.001, 1000.0);
+
+/// The number of ratios sampled inside the range before refining the best one.
+const SAMPLES: usize = 200;
+
+/// The load at the output of the transmission.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Load {
+    /// Transmission torque: M<sub>T</sub> [Nm]
+    pub m_t: f64,
+    /// Transmission speed: n<sub>T</sub> [rpm]
+    pub n_t: f64,
+    /// Load inertia: J<sub>T</sub> [kgm²]
+    pub j_t: f64,
+}
+
+/// A enum representing the goals a transmission ratio can be optimized for.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Objective {
+    /// Maximum overall efficiency.
+    Efficiency,
+    /// Minimum motor current.
+    Current,
+    /// Matching the load inertia to the rotor inertia for the best acceleration.
+    Inertia,
+}
+
+impl Objective {
+    /// Returns the cost of running the load with the transmission ratio, lower being better, or
+    /// None if the motor can't drive the load at that ratio. The transmission efficiency is given
+    /// in percent.
+    pub fn cost(&self, motor: &Motor, load: &Load, eta_t: f64, i_t: f64) -> Option<f64> {
+        let m_m = load.m_t * i_t / (eta_t / 100.0);
+        let n_m = load.n_t / i_t;
+        let (i, u) = motor.operating_point(m_m, n_m);
+
+        if i_t <= 0.0 || m_m > motor.m_max || n_m > motor.n_max || u > motor.u {
+            return None;
+        }
+
+        let cost = match self {
+            Objective::Efficiency => u * i,
+            Objective::Current => i,
+            Objective::Inertia => (load.j_t * i_t * i_t / motor.j).ln().abs(),
+        };
+
+        Some(cost)
+    }
+}
+
+/// Returns the transmission ratio i = n<sub>T</sub> / n<sub>M</sub> that is optimal for the
+/// objective or None if the motor can't drive the load at any ratio.
+pub fn optimal_ratio(motor: &Motor, load: &Load, eta_t: f64, objective: Objective) -> Option<f64> {
+    let cost = |ln_i: f64| objective.cost(motor, load, eta_t, ln_i.exp()).unwrap_or(f64::INFINITY);
+    let (min, max) = (RANGE.0.ln(), RANGE.1.ln());
+    let step = (max - min) / (SAMPLES - 1) as f64;
+
+    let best = (0..SAMPLES)
+        .map(|s| min + s as f64 * step)
+        .map(|ln_i| (ln_i, cost(ln_i)))
+        .filter(|(_, c)| c.is_finite())
+        .fold(None, |best: Option<(f64, f64)>, (ln_i, c)| match best {
+            Some((_, b)) if b <= c => best,
+            _ => Some((ln_i, c)),
+        })?;
+
+    // golden section search between the neighbours of the best sample
+    let ratio = (5_f64.sqrt() - 1.0) / 2.0;
+    let mut a = best.0 - step;
+    let mut b = best.0 + step;
+    for _ in 0..60 {
+        let x1 = b - ratio * (b - a);
+        let x2 = a + ratio * (b - a);
+        if cost(x1) <= cost(x2) {
+            b = x2;
+        } else {
+            a = x1;
+        }
+    }
+
+    let refined = (a + b) / 2.0;
+    if cost(refined) <= best.1 {
+        Some(refined.exp())
+    } else {
+        Some(best.0.exp())
+    }
+}
+
+/// Returns the ratio out of the available ones that is optimal for the objective or None if the
+/// motor can't drive the load at any of them.
+pub fn best_available_ratio(motor: &Motor, load: &Load, eta_t: f64, objective: Objective, ratios: &[f64]) -> Option<f64> {
+    ratios.iter()
+        .filter_map(|&r| objective.cost(motor, load, eta_t, r).map(|c| (r, c)))
+        .fold(None, |best: Option<(f64, f64)>, (r, c)| match best {
+            Some((_, b)) if b <= c => best,
+            _ => Some((r, c)),
+        })
+        .map(|(r, _)| r)
+}
+
+/// Parses a list of ratios separated by commas or semicolons. Every entry is either a ratio
+/// string like "14:1" or a number.
+pub fn parse_ratios(s: &str) -> Vec<f64> {
+    s.split(&[',', ';'][..])
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty())
+        .map(|r| if r.contains(':') { Num::parse_ratio(r) } else { Num::parse(r) })
+        .filter_map(|n| n.as_option())
+        .filter(|r| r.is_finite() && *r > 0.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::catalogue::motor::Motor;
+    use crate::catalogue::ratio::{Load, Objective, best_available_ratio, optimal_ratio, parse_ratios};
+
+    fn motor() -> Motor {
+        Motor {
+            name: "RE 25".into(),
+            u: 24.0,
+            r_a: 2.18,
+            k_m: 0.0302,
+            i_0: 0.0345,
+            j: 1.08e-6,
+            m_max: 0.0288,
+            n_max: 14000.0,
+            mass: 0.13,
+            cost: 120.0,
+            length: 0.0542,
+            diameter: 0.025,
+        }
+    }
+
+    #[test]
+    fn test_inertia_matching() {
+        let load = Load { m_t: 0.1, n_t: 300.0, j_t: 1.08e-4 };
+        let i_t = optimal_ratio(&motor(), &load, 90.0, Objective::Inertia).unwrap();
+        assert!((i_t - 0.1).abs() < 1e-6);
+
+        let ratios = parse_ratios("5:1, 14:1; 0.2, 19:1");
+        assert_eq!(ratios, vec![0.2, 1.0 / 14.0, 0.2, 1.0 / 19.0]);
+        assert_eq!(best_available_ratio(&motor(), &load, 90.0, Objective::Inertia, &ratios), Some(1.0 / 14.0));
+    }
+
+    #[test]
+    fn test_efficiency() {
+        let motor = motor();
+        let load = Load { m_t: 0.1, n_t: 300.0, j_t: 0.0 };
+        let i_t = optimal_ratio(&motor, &load, 90.0, Objective::Efficiency).unwrap();
+        let best = Objective::Efficiency.cost(&motor, &load, 90.0, i_t).unwrap();
+
+        for &r in &[i_t * 0.9, i_t * 1.1] {
+            if let Some(c) = Objective::Efficiency.cost(&motor, &load, 90.0, r) {
+                assert!(best <= c);
+            }
+        }
+
+        let current = optimal_ratio(&motor, &load, 90.0, Objective::Current).unwrap();
+        assert!(current <= i_t);
+    }
+}
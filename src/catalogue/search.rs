@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field::{
+    ArmatureResistance, Current, InputPower, TransmissionEfficiency, TransmissionPower, TransmissionRatio,
+    TransmissionSpeed, TransmissionTorque, Voltage,
+};
+use crate::calc::number::Num;
+use crate::catalogue::gearbox::Gearbox;
+use crate::catalogue::motor::Motor;
+
+/// The load a motor and gearbox combination has to meet and optional limits for the drive.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Requirement {
+    /// Transmission torque: M<sub>T</sub> [Nm]
+    pub m_t: f64,
+    /// Transmission speed: n<sub>T</sub> [rpm]
+    pub n_t: f64,
+    /// Maximum nominal voltage of the motor [V]
+    pub max_voltage: Option<f64>,
+    /// Maximum length of the motor and gearbox combined [m]
+    pub max_length: Option<f64>,
+    /// Maximum diameter of the motor [m]
+    pub max_diameter: Option<f64>,
+}
+
+impl Requirement {
+    /// Creates a new requirement without any limits.
+    pub fn new(m_t: f64, n_t: f64) -> Requirement {
+        Requirement {
+            m_t,
+            n_t,
+            max_voltage: None,
+            max_length: None,
+            max_diameter: None,
+        }
+    }
+}
+
+/// A enum representing the criteria the candidates can be ranked by.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rank {
+    /// Highest overall efficiency first.
+    Efficiency,
+    /// Highest margin to the ratings first.
+    Margin,
+    /// Lowest mass first.
+    Mass,
+    /// Lowest cost first.
+    Cost,
+}
+
+/// A motor and gearbox combination that meets the requirement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate<'a> {
+    pub motor: &'a Motor,
+    pub gearbox: &'a Gearbox,
+    /// The solved calculation at the required operating point.
+    pub calc: Calculation,
+    /// Overall efficiency: P<sub>T</sub> / P<sub>in</sub> [%]
+    pub efficiency: f64,
+    /// The smallest relative reserve to any of the motor and gearbox ratings.
+    pub margin: f64,
+    /// Combined mass [kg]
+    pub mass: f64,
+    /// Combined cost
+    pub cost: f64,
+}
+
+/// Searches every motor and gearbox combination of the catalogue for the ones meeting the
+/// requirement and returns them ranked by the criterion.
+pub fn search<'a>(motors: &'a [Motor], gearboxes: &'a [Gearbox], req: &Requirement, rank: Rank) -> Vec<Candidate<'a>> {
+    let mut candidates = Vec::new();
+
+    for motor in motors {
+        if !fits(motor, req) {
+            continue;
+        }
+
+        for gearbox in gearboxes {
+            if let Some(c) = evaluate(motor, gearbox, req) {
+                candidates.push(c);
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| compare(a, b, rank));
+
+    candidates
+}
+
+/// Returns true if the motor meets the voltage and size limits false otherwise.
+fn fits(motor: &Motor, req: &Requirement) -> bool {
+    !matches!(req.max_voltage, Some(v) if motor.u > v) && !matches!(req.max_diameter, Some(d) if motor.diameter > d)
+}
+
+/// Solves the combination at the required operating point. Returns None if the combination
+/// exceeds any of its ratings or the length limit.
+fn evaluate<'a>(motor: &'a Motor, gearbox: &'a Gearbox, req: &Requirement) -> Option<Candidate<'a>> {
+    if matches!(req.max_length, Some(l) if motor.length + gearbox.length > l) {
+        return None;
+    }
+
+    let m_m = req.m_t * gearbox.i_t / (gearbox.eta_t / 100.0);
+    let n_m = req.n_t / gearbox.i_t;
+    let (i, u) = motor.operating_point(m_m, n_m);
+
+    let margin = [
+        motor.u / u,
+        motor.m_max / m_m,
+        motor.n_max / n_m,
+        gearbox.m_max / req.m_t,
+    ].iter().fold(f64::INFINITY, |a, b| a.min(*b)) - 1.0;
+
+    if margin < 0.0 {
+        return None;
+    }
+
+    let mut calc = Calculation::new();
+    calc[Voltage] = Num::In(u);
+    calc[Current] = Num::In(i);
+    calc[ArmatureResistance] = Num::In(motor.r_a);
+    calc[TransmissionRatio] = Num::In(gearbox.i_t);
+    calc[TransmissionEfficiency] = Num::In(gearbox.eta_t);
+    calc[TransmissionTorque] = Num::In(req.m_t);
+    calc[TransmissionSpeed] = Num::In(req.n_t);
+
+    let calc = *calc.solutions().first()?;
+    let efficiency = (calc[TransmissionPower] / calc[InputPower] * 100.0).as_option()?;
+
+    Some(Candidate {
+        motor,
+        gearbox,
+        calc,
+        efficiency,
+        margin,
+        mass: motor.mass + gearbox.mass,
+        cost: motor.cost + gearbox.cost,
+    })
+}
+
+/// Orders the candidates so that the better one according to the criterion comes first.
+fn compare(a: &Candidate, b: &Candidate, rank: Rank) -> Ordering {
+    let ordering = match rank {
+        Rank::Efficiency => b.efficiency.partial_cmp(&a.efficiency),
+        Rank::Margin => b.margin.partial_cmp(&a.margin),
+        Rank::Mass => a.mass.partial_cmp(&b.mass),
+        Rank::Cost => a.cost.partial_cmp(&b.cost),
+    };
+
+    ordering.unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::field::Field::{MotorSpeed, TransmissionTorque};
+    use crate::catalogue::gearbox::Gearbox;
+    use crate::catalogue::motor::Motor;
+    use crate::catalogue::search::{Candidate, Rank, Requirement, search};
+
+    fn motor(name: &str, u: f64, k_m: f64, mass: f64, cost: f64) -> Motor {
+        Motor {
+            name: name.into(),
+            u,
+            r_a: 0.5,
+            k_m,
+            i_0: 0.1,
+            j: 1e-6,
+            m_max: 0.5,
+            n_max: 8000.0,
+            mass,
+            cost,
+            length: 0.05,
+            diameter: 0.03,
+        }
+    }
+
+    fn gearbox(name: &str, i_t: f64, eta_t: f64) -> Gearbox {
+        Gearbox {
+            name: name.into(),
+            i_t,
+            eta_t,
+            m_max: 10.0,
+            mass: 0.1,
+            cost: 20.0,
+            length: 0.04,
+        }
+    }
+
+    fn names<'a>(candidates: &'a [Candidate]) -> Vec<(&'a str, &'a str)> {
+        candidates.iter().map(|c| (c.motor.name.as_str(), c.gearbox.name.as_str())).collect()
+    }
+
+    #[test]
+    fn test_search() {
+        let motors = [
+            motor("small", 12.0, 0.02, 0.2, 30.0),
+            motor("large", 24.0, 0.05, 0.5, 60.0),
+        ];
+        let gearboxes = [
+            gearbox("5:1", 0.2, 90.0),
+            gearbox("20:1", 0.05, 80.0),
+        ];
+        let req = Requirement::new(2.0, 100.0);
+
+        // the small motor can't reach the speed of the 5:1 gearbox with its voltage
+        let candidates = search(&motors, &gearboxes, &req, Rank::Cost);
+        assert_eq!(names(&candidates), vec![("small", "20:1"), ("large", "5:1"), ("large", "20:1")]);
+        for c in &candidates {
+            assert!(c.margin >= 0.0);
+            assert!(c.efficiency > 0.0 && c.efficiency < 100.0);
+            assert_eq!(c.calc[TransmissionTorque].num(), 2.0);
+            assert!((c.calc[MotorSpeed].num() - 100.0 / c.gearbox.i_t).abs() < 1e-9);
+        }
+        for w in candidates.windows(2) {
+            assert!(w[0].cost <= w[1].cost);
+        }
+
+        let candidates = search(&motors, &gearboxes, &req, Rank::Efficiency);
+        assert_eq!(names(&candidates), vec![("large", "20:1"), ("small", "20:1"), ("large", "5:1")]);
+
+        let mut limited = req;
+        limited.max_voltage = Some(12.0);
+        assert_eq!(names(&search(&motors, &gearboxes, &limited, Rank::Efficiency)), vec![("small", "20:1")]);
+
+        limited.max_length = Some(0.08);
+        assert!(search(&motors, &gearboxes, &limited, Rank::Efficiency).is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+use std::fs::File;
+use std::io::{Read, Write};
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::error::ErrorKind;
+
+/// A enum representing the result of solving a single row.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    Ok,
+    Overconstrained,
+    Underconstrained,
+    /// The inputs are met by the number of operating points, the one with the lowest current is
+    /// written.
+    Ambiguous(usize),
+    /// The value of the column couldn't be parsed.
+    Invalid(String),
+}
+
+impl Status {
+    fn as_string(&self) -> String {
+        match self {
+            Status::Ok => "ok".to_string(),
+            Status::Overconstrained => "overconstrained".to_string(),
+            Status::Underconstrained => "underconstrained".to_string(),
+            Status::Ambiguous(n) => format!("first of {} solutions", n),
+            Status::Invalid(column) => format!("invalid value in {}", column),
+        }
+    }
+}
+
+/// Solves the CSV table of input sets given by the arguments `<input> [output]`. The solved table
+/// is returned if no output file was given, a summary otherwise.
+pub fn run(args: &[String]) -> Result<String, String> {
+    let (input, output) = match args {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => return Err("Usage: motorcalc-cli batch <input.csv> [output.csv]".to_string()),
+    };
+
+    let reader = File::open(input).map_err(|e| format!("Couldn't open '{}': {}", input, e))?;
+    let mut out = Vec::new();
+    let statuses = solve_csv(reader, &mut out)?;
+
+    match output {
+        Some(path) => {
+            File::create(path)
+                .and_then(|mut f| f.write_all(&out))
+                .map_err(|e| format!("Couldn't write '{}': {}", path, e))?;
+            Ok(summary(&statuses))
+        }
+        None => String::from_utf8(out).map_err(|e| e.to_string()),
+    }
+}
+
+/// Reads a CSV table where every column is named after a field and every row is a set of
+/// inputs, solves every row and writes a table with all fields and the status of every row.
+/// Empty cells are treated as missing inputs.
+pub fn solve_csv(reader: impl Read, writer: impl Write) -> Result<Vec<Status>, String> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut writer = csv::Writer::from_writer(writer);
+    let mut statuses = Vec::new();
+
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    if let Some(h) = headers.iter().find(|h| Field::from_id(h.trim()).is_none()) {
+        return Err(format!("Unknown field '{}'", h));
+    }
+
+    let mut header = Field::ALL.iter().map(|f| f.id()).collect::<Vec<&str>>();
+    header.push("status");
+    writer.write_record(&header).map_err(|e| e.to_string())?;
+
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let (calc, status) = solve_record(&headers, &record);
+
+        let mut row = Field::ALL.iter()
+            .map(|f| calc.get(*f).as_option().map_or(String::new(), |v| v.to_string()))
+            .collect::<Vec<String>>();
+        row.push(status.as_string());
+        writer.write_record(&row).map_err(|e| e.to_string())?;
+
+        statuses.push(status);
+    }
+
+    writer.flush().map_err(|e| e.to_string())?;
+
+    Ok(statuses)
+}
+
+/// Solves a single row of inputs.
+fn solve_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> (Calculation, Status) {
+    let mut calc = Calculation::new();
+
+    for (header, value) in headers.iter().zip(record.iter()) {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        if let Some(field) = Field::from_id(header.trim()) {
+            let num = field.parse(value);
+            if num.is_none() {
+                return (calc, Status::Invalid(field.id().to_string()));
+            }
+            *calc.get_mut(field) = num;
+        }
+    }
+
+    let solutions = calc.solutions();
+    let calc = solutions.first().copied().unwrap_or_else(|| calc.fill_missing());
+
+    let status = match calc.check() {
+        Ok(()) if solutions.len() > 1 => Status::Ambiguous(solutions.len()),
+        Ok(()) => Status::Ok,
+        Err(e) => match e.kind {
+            ErrorKind::Overconstrained => Status::Overconstrained,
+            _ => Status::Underconstrained,
+        },
+    };
+
+    (calc, status)
+}
+
+/// Returns a summary of how many rows were solved successfully.
+fn summary(statuses: &[Status]) -> String {
+    let count = |s: &Status| statuses.iter().filter(|t| *t == s).count();
+    let ambiguous = statuses.iter().filter(|s| matches!(s, Status::Ambiguous(_))).count();
+    let invalid = statuses.iter().filter(|s| matches!(s, Status::Invalid(_))).count();
+
+    format!(
+        "Solved {} rows: {} ok, {} with more than one solution, {} overconstrained, {} underconstrained, {} invalid\n",
+        statuses.len(),
+        count(&Status::Ok),
+        ambiguous,
+        count(&Status::Overconstrained),
+        count(&Status::Underconstrained),
+        invalid,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::batch::{Status, solve_csv};
+
+    #[test]
+    fn test_solve_csv() {
+        let csv = "\
+u,i,r_a,eta_m,n_t,i_t,eta_t
+12,2.5,0.5,80,300,5:1,90
+12,2.5,,80,300,5:1,90
+12,2.5,0.5,80,300,5:1,x
+";
+        let mut out = Vec::new();
+        let statuses = solve_csv(csv.as_bytes(), &mut out).unwrap();
+        assert_eq!(statuses, vec![
+            Status::Ok,
+            Status::Underconstrained,
+            Status::Invalid("eta_t".to_string()),
+        ]);
+
+        let out = String::from_utf8(out).unwrap();
+        let mut lines = out.lines();
+        assert!(lines.next().unwrap().ends_with("i_t,eta_t,status"));
+        assert!(lines.next().unwrap().starts_with("30,12,2.5,0.5,"));
+
+        // a motor delivering a power at a voltage has a low and a high current operating point
+        let mut out = Vec::new();
+        let csv = "u,r_a,p_m,p_m_l_mech,n_m,i_t,eta_t\n24,2,20,1,3000,5:1,90\n";
+        assert_eq!(solve_csv(csv.as_bytes(), &mut out).unwrap(), vec![Status::Ambiguous(2)]);
+        let out = String::from_utf8(out).unwrap();
+        let row = out.lines().nth(1).unwrap().split(',').collect::<Vec<&str>>();
+        assert!(row[2].parse::<f64>().unwrap() < 1.0);
+        assert_eq!(row.last(), Some(&"first of 2 solutions"));
+
+        let csv = "u,i,p_in\n12,2.5,31\n";
+        assert_eq!(solve_csv(csv.as_bytes(), Vec::new()).unwrap(), vec![Status::Overconstrained]);
+        assert!(solve_csv("u,x\n1,2\n".as_bytes(), Vec::new()).is_err());
+    }
+}
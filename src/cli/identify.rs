@@ -0,0 +1,100 @@
+use std::fs::File;
+use std::io::Read;
+
+use crate::calc::field::Field::{self, Current, MotorSpeed, MotorTorque, Voltage};
+use crate::calc::identify::{identify, Model, Point};
+
+/// The columns needed for every measured point.
+const COLUMNS: [Field; 4] = [Voltage, Current, MotorSpeed, MotorTorque];
+
+/// Fits the motor parameters to the CSV table of measured points given by the argument
+/// `<points>` and returns them with the goodness of fit.
+pub fn run(args: &[String]) -> Result<String, String> {
+    let input = match args {
+        [input] => input,
+        _ => return Err("Usage: motorcalc-cli identify <points.csv>".to_string()),
+    };
+
+    let reader = File::open(input).map_err(|e| format!("Couldn't open '{}': {}", input, e))?;
+    let points = read_points(reader)?;
+    let model = identify(&points).map_err(|e| e.to_string())?;
+
+    Ok(format_model(&model, points.len()))
+}
+
+/// Reads a CSV table with the columns u, i, n_m and m_m. Other columns are ignored, values
+/// accept metric prefixes.
+pub fn read_points(reader: impl Read) -> Result<Vec<Point>, String> {
+    let mut reader = csv::Reader::from_reader(reader);
+
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let indices = COLUMNS.iter()
+        .map(|c| headers.iter().position(|h| h.trim() == c.id()).ok_or(format!("Missing column '{}'", c.id())))
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    let mut points = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| e.to_string())?;
+
+        let values = COLUMNS.iter()
+            .zip(&indices)
+            .map(|(field, i)| {
+                field.parse(record.get(*i).unwrap_or_default().trim())
+                    .as_option()
+                    .ok_or(format!("Invalid value in {} of row {}", field.id(), row + 1))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+
+        points.push(Point::new(values[0], values[1], values[2], values[3]));
+    }
+
+    Ok(points)
+}
+
+/// Formats the fitted parameters and the goodness of both regressions as a table.
+pub fn format_model(model: &Model, points: usize) -> String {
+    let rows = [
+        ("R_A", model.r_a, "Ω", "Armature resistance"),
+        ("k_t", model.k_t, "Nm/A", "Torque constant"),
+        ("k_e", model.k_e, "Vs/rad", "Back EMF constant"),
+        ("I_0", model.i_0, "A", "No load current"),
+        ("M_f", model.m_f, "Nm", "Friction torque"),
+    ];
+
+    let mut out = format!("Fitted to {} points\n", points);
+    for (symbol, value, unit, description) in rows.iter() {
+        out.push_str(&format!("{:<4} {:>14.6} {:<7} {}\n", symbol, value, unit, description));
+    }
+    out.push_str(&format!("\nVoltage fit: R² = {:.6}, RMSE = {:.4e} V\n", model.voltage.r_squared, model.voltage.rmse));
+    out.push_str(&format!("Torque fit:  R² = {:.6}, RMSE = {:.4e} Nm\n", model.torque.r_squared, model.torque.rmse));
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::identify::identify;
+    use crate::cli::identify::{format_model, read_points};
+
+    #[test]
+    fn test_read_points() {
+        let csv = "\
+m_m,n_m,u,i,eta_m
+100m,3000,12,2.5,
+0.3,2.5k,12,5,80
+0.5,2000,12,7.5,
+";
+        let points = read_points(csv.as_bytes()).unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].m_m, 0.1);
+        assert_eq!(points[1].n_m, 2500.0);
+
+        let model = identify(&points).unwrap();
+        let out = format_model(&model, points.len());
+        assert!(out.starts_with("Fitted to 3 points\n"));
+        assert!(out.contains("Torque fit:  R² = 1.000000"));
+
+        assert!(read_points("u,i,n_m\n1,2,3\n".as_bytes()).is_err());
+        assert!(read_points("u,i,n_m,m_m\n1,2,3,\n".as_bytes()).is_err());
+    }
+}
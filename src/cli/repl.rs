@@ -0,0 +1,229 @@
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::validation::validate;
+use crate::cli::solve::{format_issues, format_table, solve_branch};
+
+/// The commands of the REPL in addition to the field names.
+const COMMANDS: [&str; 6] = ["show", "solve", "clear", "help", "quit", "exit"];
+
+/// A structure holding the state of an interactive session.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Repl {
+    pub calc: Calculation,
+    pub significant_figures: usize,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self {
+            calc: Calculation::new(),
+            significant_figures: 6,
+        }
+    }
+
+    /// Executes a line like `u = 24`, `show`, `clear m_m` or `solve` and returns the output or
+    /// None if the session should be ended.
+    pub fn execute(&mut self, line: &str) -> Option<Result<String, String>> {
+        let line = line.trim();
+
+        if let Some(i) = line.find('=') {
+            return Some(self.assign(line[..i].trim(), line[i + 1..].trim()));
+        }
+
+        let mut words = line.split_whitespace();
+        let result = match (words.next(), words.next()) {
+            (None, _) => Ok(String::new()),
+            (Some("quit"), _) | (Some("exit"), _) => return None,
+            (Some("help"), _) => Ok(help()),
+            (Some("show"), _) => Ok(format_table(&self.calc, self.significant_figures)),
+            (Some("solve"), None) => self.solve(1),
+            (Some("solve"), Some(n)) => match n.parse() {
+                Ok(n) => self.solve(n),
+                Err(_) => Err(format!("Invalid solution '{}'", n)),
+            },
+            (Some("clear"), None) => {
+                self.calc = Calculation::new();
+                Ok(String::new())
+            }
+            (Some("clear"), Some(id)) => self.assign(id, ""),
+            (Some(word), _) => match Field::from_id(word) {
+                Some(_) => Ok(format_table(&self.calc, self.significant_figures)
+                    .lines()
+                    .find(|l| l.split_whitespace().next() == Some(word))
+                    .map_or(String::new(), |l| format!("{}\n", l))),
+                None => Err(format!("Unknown command '{}', type 'help' for a list of commands", word)),
+            },
+        };
+
+        Some(result)
+    }
+
+    /// Sets the input of the field with the id, an empty value clears it. Calculated values are
+    /// cleared since they may not be valid anymore.
+    fn assign(&mut self, id: &str, value: &str) -> Result<String, String> {
+        let field = Field::from_id(id).ok_or_else(|| format!("Unknown field '{}'", id))?;
+        let mut calc = self.calc.clear_output();
+        let num = calc.get_mut(field);
+
+        *num = field.parse(value.trim_end_matches('%'));
+        if num.is_none() && !value.is_empty() {
+            return Err(format!("Invalid value '{}' for '{}'", value, id));
+        }
+
+        self.calc = calc;
+        Ok(String::new())
+    }
+
+    /// Solves the calculation and returns the table of all fields. If there is more than one
+    /// solution the one with the 1-based index is shown.
+    fn solve(&mut self, branch: usize) -> Result<String, String> {
+        let (calc, _, solutions) = solve_branch(&self.calc, branch)?;
+        self.calc = calc;
+
+        let mut out = format_table(&self.calc, self.significant_figures);
+        out.push_str(&format_issues(&validate(&self.calc)));
+        if solutions > 1 {
+            out.push_str(&format!("Solution {} of {}, choose another one with 'solve <n>'\n", branch, solutions));
+        }
+        Ok(out)
+    }
+}
+
+/// Returns the start of the word at the position and the field names and commands that start
+/// with it.
+pub fn complete(line: &str, pos: usize) -> (usize, Vec<String>) {
+    let start = line[..pos].rfind(|c: char| c.is_whitespace() || c == '=').map_or(0, |i| i + 1);
+    let word = &line[start..pos];
+
+    let candidates = Field::ALL.iter()
+        .map(|f| f.id())
+        .chain(COMMANDS.iter().copied())
+        .filter(|c| c.starts_with(word))
+        .map(String::from)
+        .collect();
+
+    (start, candidates)
+}
+
+/// Returns the help text of the REPL.
+fn help() -> String {
+    let mut out = String::from("\
+Commands:
+  <field> = <value>   set an input, values accept metric prefixes like 2.5k or a trailing %
+  <field>             show a single field
+  show                show all fields
+  solve [<n>]         calculate the missing fields, continue with the n-th solution if
+                      there is more than one
+  clear [<field>]     clear a single input or all of them
+  quit                end the session
+
+Fields:
+");
+
+    for f in Field::ALL.iter() {
+        out.push_str(&format!("  {:<12} {}\n", f.id(), f.description()));
+    }
+
+    out
+}
+
+/// Completes field names and commands.
+struct FieldHelper;
+
+impl Completer for FieldHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(complete(line, pos))
+    }
+}
+
+impl Hinter for FieldHelper {
+    type Hint = String;
+}
+
+impl Highlighter for FieldHelper {}
+
+impl Validator for FieldHelper {}
+
+impl Helper for FieldHelper {}
+
+/// Runs an interactive session reading from the terminal until it is ended.
+pub fn run() -> Result<String, String> {
+    let mut editor = Editor::<FieldHelper>::new();
+    editor.set_helper(Some(FieldHelper));
+    let mut repl = Repl::new();
+
+    loop {
+        let line = match editor.readline("motorcalc> ") {
+            Ok(l) => l,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.to_string()),
+        };
+        editor.add_history_entry(line.as_str());
+
+        match repl.execute(&line) {
+            Some(Ok(out)) => print!("{}", out),
+            Some(Err(e)) => eprintln!("error: {}", e),
+            None => break,
+        }
+    }
+
+    Ok(String::new())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::field::Field::{ArmatureResistance, Current, InputPower, MotorEfficiency, Voltage};
+    use crate::calc::number::Num;
+    use crate::cli::repl::{complete, Repl};
+
+    #[test]
+    fn test_execute() {
+        let mut repl = Repl::new();
+
+        for line in &["u = 24", "i=2.5", "eta_m = 78%", "r_a = 500m"] {
+            assert_eq!(repl.execute(line), Some(Ok(String::new())));
+        }
+        assert_eq!(repl.calc[MotorEfficiency], Num::In(78.0));
+        assert_eq!(repl.calc[ArmatureResistance], Num::In(0.5));
+
+        assert!(repl.execute("solve").unwrap().unwrap().contains("p_in"));
+        assert_eq!(repl.calc[InputPower], Num::Out(60.0));
+
+        repl.execute("clear u");
+        assert_eq!(repl.calc[Voltage], Num::None);
+        assert_eq!(repl.calc[InputPower], Num::None);
+        assert_eq!(repl.calc[Current], Num::In(2.5));
+
+        assert!(repl.execute("x = 3").unwrap().is_err());
+        assert!(repl.execute("u = abc").unwrap().is_err());
+        assert!(repl.execute("frobnicate").unwrap().is_err());
+        assert!(repl.execute("i").unwrap().unwrap().starts_with("i "));
+
+        repl.execute("clear");
+        assert_eq!(repl.calc[Current], Num::None);
+        assert_eq!(repl.execute("quit"), None);
+    }
+
+    #[test]
+    fn test_complete() {
+        assert_eq!(complete("p_m_l", 5), (0, vec!["p_m_l_el".to_string(), "p_m_l_mech".to_string(), "p_m_l".to_string()]));
+        assert_eq!(complete("clear eta", 9), (6, vec!["eta_m".to_string(), "eta_t".to_string()]));
+        assert_eq!(complete("n_t=", 4).1.len(), 17 + 6);
+        assert_eq!(complete("so", 2), (0, vec!["solve".to_string()]));
+    }
+}
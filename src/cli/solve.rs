@@ -0,0 +1,239 @@
+use serde_json::{Map, Value};
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::newton::Report;
+use crate::calc::number::Num;
+use crate::calc::validation::{validate, Issue, Severity};
+use crate::error::ErrorKind;
+
+/// A structure holding the options of the solve command.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    pub calc: Calculation,
+    pub json: bool,
+    pub help: bool,
+    pub significant_figures: usize,
+    /// The 1-based index of the solution to continue with if there is more than one.
+    pub branch: usize,
+}
+
+/// Parses arguments like `--u 12 --i 2.5 --eta_m 80 --json`. Values may also be given as
+/// `--u=12` and are parsed like the inputs of the web app.
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        calc: Calculation::new(),
+        json: false,
+        help: false,
+        significant_figures: 6,
+        branch: 1,
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let arg = arg.strip_prefix("--").ok_or_else(|| format!("Unexpected argument '{}'", arg))?;
+        let (name, inline_value) = match arg.find('=') {
+            Some(i) => (&arg[..i], Some(&arg[i + 1..])),
+            None => (arg, None),
+        };
+
+        match name {
+            "json" => options.json = true,
+            "help" => options.help = true,
+            _ => {
+                let value = inline_value
+                    .or_else(|| args.next().map(String::as_str))
+                    .ok_or_else(|| format!("Missing value for '--{}'", name))?;
+
+                if name == "significant-figures" {
+                    options.significant_figures = value.parse()
+                        .map_err(|_| format!("Invalid number of significant figures '{}'", value))?;
+                    continue;
+                }
+                if name == "branch" {
+                    options.branch = value.parse()
+                        .map_err(|_| format!("Invalid solution '{}'", value))?;
+                    continue;
+                }
+
+                let field = Field::from_id(name).ok_or_else(|| format!("Unknown field '{}'", name))?;
+                let num = options.calc.get_mut(field);
+                *num = field.parse(value.trim_end_matches('%'));
+                if num.is_none() {
+                    return Err(format!("Invalid value '{}' for '--{}'", value, name));
+                }
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Solves the calculation given by the arguments and returns the output.
+pub fn run(args: &[String]) -> Result<String, String> {
+    let options = parse_args(args)?;
+
+    if options.help {
+        return Ok(usage());
+    }
+
+    let (calc, report, solutions) = solve_branch(&options.calc, options.branch)?;
+
+    if options.json {
+        Ok(format_json(&calc))
+    } else {
+        let mut out = format_table(&calc, options.significant_figures);
+        out.push_str(&format_issues(&validate(&calc)));
+        match calc.check() {
+            Err(e) if e.kind == ErrorKind::Overconstrained => out.push_str(&format!("error: {}\n", e)),
+            _ => (),
+        }
+        if solutions > 1 {
+            out.push_str(&format!("\nSolution {} of {}, choose another one with --branch <n>\n", options.branch, solutions));
+        } else if let Some(r) = report {
+            out.push_str(&format!("\n{}\n", r.description()));
+        }
+        Ok(out)
+    }
+}
+
+/// Solves the calculation and continues with the solution with the 1-based index if there is
+/// more than one. Returns the solved calculation, the number of solutions and, if there is no
+/// valid solution, the report of the simultaneous solve explaining why.
+pub fn solve_branch(calc: &Calculation, branch: usize) -> Result<(Calculation, Option<Report>, usize), String> {
+    let solutions = calc.solutions();
+
+    match solutions.len() {
+        0 => {
+            let (solved, report) = calc.solve();
+            Ok((solved, report, 0))
+        }
+        1 => Ok((solutions[0], None, 1)),
+        n => match branch.checked_sub(1).and_then(|i| solutions.get(i)) {
+            Some(c) => Ok((*c, None, n)),
+            None => Err(format!("There are only {} solutions", n)),
+        },
+    }
+}
+
+/// Formats all fields with their units as a table. Inputs are marked as such.
+pub fn format_table(calc: &Calculation, significant_figures: usize) -> String {
+    let mut out = String::new();
+
+    for f in Field::ALL.iter() {
+        let num = calc.get(*f);
+        let value = match num {
+            Num::None => "-".to_string(),
+            n => format!("{}{}", f.display(n, significant_figures), f.unit()),
+        };
+        let marker = if num.is_input() { "  (input)" } else { "" };
+
+        out.push_str(&format!("{:<10} {:>16}{}\n", f.id(), value, marker));
+    }
+
+    out
+}
+
+/// Formats the violated constraints with a line for every issue.
+pub fn format_issues(issues: &[Issue]) -> String {
+    let mut out = String::new();
+
+    for i in issues {
+        let severity = match i.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        out.push_str(&format!("{}: {}: {}\n", severity, i.field.id(), i.message));
+    }
+
+    out
+}
+
+/// Formats all fields as a JSON object mapping the ids to the values in the base units. Missing
+/// values are null.
+pub fn format_json(calc: &Calculation) -> String {
+    let map = Field::ALL.iter()
+        .map(|f| {
+            let value = calc.get(*f).as_option().map_or(Value::Null, Value::from);
+            (f.id().to_string(), value)
+        })
+        .collect::<Map<String, Value>>();
+
+    format!("{}\n", Value::Object(map))
+}
+
+/// Returns the usage of the command line interface listing all fields.
+pub fn usage() -> String {
+    let mut out = String::from("\
+Usage: motorcalc-cli [--<field> <value>]... [--json] [--significant-figures <n>] [--branch <n>]
+       motorcalc-cli batch <input.csv> [output.csv]
+       motorcalc-cli identify <points.csv>
+       motorcalc-cli repl
+       motorcalc-cli tui
+
+Solves the calculation for the given fields. Values accept metric prefixes like 2.5k or 10m, the
+transmission ratio is given as a ratio like 5:1. If the inputs are met by more than one operating
+point, like a low and a high current one, --branch chooses the solution to show. In batch mode
+every row of the CSV file, with the field ids as header, is solved and written with a status
+column. Identify fits the parameters of a DC motor to measured points with the columns u, i, n_m
+and m_m. The repl starts an interactive session, type 'help' there for a list of commands. The tui
+shows the same form as the web app in the terminal.
+
+Fields:
+");
+
+    for f in Field::ALL.iter() {
+        let unit = if f.unit().is_empty() { String::new() } else { format!(" [{}]", f.unit()) };
+        out.push_str(&format!("  --{:<12} {}{}\n", f.id(), f.description(), unit));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::field::Field::{Current, MotorEfficiency, TransmissionRatio, Voltage};
+    use crate::calc::number::Num;
+    use crate::cli::solve::{parse_args, run};
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args("--u 12 --i=2.5k --eta_m 80% --i_t 5:1 --json")).unwrap();
+        assert_eq!(options.calc[Voltage], Num::In(12.0));
+        assert_eq!(options.calc[Current], Num::In(2500.0));
+        assert_eq!(options.calc[MotorEfficiency], Num::In(80.0));
+        assert_eq!(options.calc[TransmissionRatio], Num::In(0.2));
+        assert!(options.json);
+
+        assert!(parse_args(&args("--x 12")).is_err());
+        assert!(parse_args(&args("--u")).is_err());
+        assert!(parse_args(&args("--u abc")).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let table = run(&args("--u 12 --i 2.5")).unwrap();
+        assert!(table.lines().any(|l| l.starts_with("p_in") && l.contains("30.0000 W")));
+        assert!(table.lines().any(|l| l.starts_with("u") && l.ends_with("(input)")));
+
+        let json = run(&args("--u 12 --i 2.5 --json")).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["p_in"], 30.0);
+        assert!(value["r_a"].is_null());
+
+        let table = run(&args("--u 12 --i 2.5 --eta_m 120")).unwrap();
+        assert!(table.contains("error: eta_m: The efficiency has to be above 0 % and at most 100 %\n"));
+
+        let table = run(&args("--u 12 --i 2.5 --p_in 31")).unwrap();
+        assert!(table.ends_with("error: The values contradict each other in u · i = p_in (fields: u, i, p_in) with u = 12, i = 2.5, p_in = 31, residual -1e0\n"));
+
+        let table = run(&args("--u 12 --r_a 0.5 --p_m 40 --p_m_l_mech 0 --branch 2")).unwrap();
+        assert!(table.lines().any(|l| l.starts_with("i ") && l.contains("20.0000 A")));
+        assert!(table.ends_with("Solution 2 of 2, choose another one with --branch <n>\n"));
+        assert!(run(&args("--u 12 --r_a 0.5 --p_m 40 --p_m_l_mech 0 --branch 3")).is_err());
+    }
+}
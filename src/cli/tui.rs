@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use crate::calc::calculation::Calculation;
+use crate::calc::field::Field;
+use crate::calc::validation::{issues_of, validate, Issue, Severity};
+
+/// The number of characters between two columns of the grid.
+const COLUMN_WIDTH: u16 = 9;
+/// The number of lines between two rows of the grid.
+const ROW_HEIGHT: u16 = 3;
+/// The width of a single field.
+const FIELD_WIDTH: u16 = 18;
+
+/// A structure holding the state of the form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Form {
+    pub calc: Calculation,
+    pub inputs: HashMap<Field, String>,
+    pub selected: usize,
+    pub significant_figures: usize,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self {
+            calc: Calculation::new(),
+            inputs: HashMap::new(),
+            selected: 0,
+            significant_figures: 4,
+        }
+    }
+
+    /// Handles a key press and returns true if the form should be closed false otherwise.
+    pub fn key(&mut self, key: KeyEvent) -> bool {
+        let field = Field::ALL[self.selected];
+        let mut input = self.inputs.get(&field).cloned().unwrap_or_default();
+
+        match key.code {
+            KeyCode::Esc => return true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return true,
+            KeyCode::Tab | KeyCode::Down | KeyCode::Enter => self.selected = (self.selected + 1) % Field::ALL.len(),
+            KeyCode::BackTab | KeyCode::Up => self.selected = (self.selected + Field::ALL.len() - 1) % Field::ALL.len(),
+            _ if self.is_disabled(field) => (),
+            KeyCode::Char(c) => {
+                input.push(c);
+                self.edit(field, input);
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                self.edit(field, input);
+            }
+            KeyCode::Delete => self.edit(field, String::new()),
+            _ => (),
+        }
+
+        false
+    }
+
+    /// Sets the input of the field and solves the calculation again.
+    pub fn edit(&mut self, field: Field, s: String) {
+        *self.calc.get_mut(field) = field.parse(s.clone());
+        self.inputs.insert(field, s);
+
+        // continue with the low current operating point if there is more than one
+        self.calc = self.calc.solutions().first().copied().unwrap_or_else(|| self.calc.fill_missing());
+    }
+
+    /// Returns true if the field holds a calculated value and can't be edited false otherwise.
+    pub fn is_disabled(&self, field: Field) -> bool {
+        self.calc.get(field).is_output()
+    }
+
+    /// Draws the grid of fields into the frame.
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Motor Calculator · Tab next · Shift+Tab previous · Del clear · Esc quit ");
+        let area = block.inner(f.size());
+        f.render_widget(block, f.size());
+
+        let issues = validate(&self.calc);
+
+        for (i, field) in Field::ALL.iter().enumerate() {
+            let (column, row) = field.position();
+            let rect = Rect::new(area.x + column * COLUMN_WIDTH, area.y + row * ROW_HEIGHT, FIELD_WIDTH, 2)
+                .intersection(area);
+            f.render_widget(self.field(*field, i == self.selected, &issues_of(&issues, *field)), rect);
+
+            if i == self.selected && !self.is_disabled(*field) {
+                let len = self.inputs.get(field).map_or(0, |s| s.chars().count()) as u16;
+                f.set_cursor((rect.x + len).min(rect.right().saturating_sub(1)), rect.y + 1);
+            }
+        }
+
+        // the issues of the selected field are listed at the bottom
+        let selected = issues_of(&issues, Field::ALL[self.selected]);
+        let height = (selected.len() as u16).min(area.height);
+        let rect = Rect::new(area.x, area.bottom() - height, area.width, height);
+        let lines = selected.iter()
+            .map(|i| Spans::from(Span::styled(i.message, Style::default().fg(severity_color(i.severity)))))
+            .collect::<Vec<Spans>>();
+        f.render_widget(Paragraph::new(lines), rect);
+    }
+
+    /// Returns the label and the value of the field. Calculated values are dimmed like the
+    /// disabled inputs of the web app, the label of a field with issues is colored by their
+    /// severity.
+    fn field(&self, field: Field, selected: bool, issues: &[Issue]) -> Paragraph<'static> {
+        let num = self.calc.get(field);
+        let value = if num.is_output() {
+            Span::styled(field.display(num, self.significant_figures), Style::default().fg(Color::DarkGray))
+        } else {
+            Span::styled(self.inputs.get(&field).cloned().unwrap_or_default(), Style::default().add_modifier(Modifier::UNDERLINED))
+        };
+
+        let mut label_style = Style::default().fg(Color::Gray);
+        if selected {
+            label_style = label_style.fg(Color::White).add_modifier(Modifier::BOLD);
+        }
+        let severity = if issues.iter().any(|i| i.severity == Severity::Error) {
+            Some(Severity::Error)
+        } else {
+            issues.first().map(|i| i.severity)
+        };
+        if let Some(s) = severity {
+            label_style = label_style.fg(severity_color(s));
+        }
+
+        Paragraph::new(vec![
+            Spans::from(Span::styled(label(field), label_style)),
+            Spans::from(value),
+        ])
+    }
+}
+
+/// Returns the color of issues with the severity.
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+    }
+}
+
+/// Returns the symbol and the unit of the field.
+fn label(field: Field) -> String {
+    let sub = if field.sub().is_empty() { String::new() } else { format!("_{}", field.sub()) };
+    let unit = if field.unit().is_empty() { String::new() } else { format!(" [{}]", field.unit()) };
+
+    format!("{}{}{}", field.symbol(), sub, unit)
+}
+
+/// Runs the form in the terminal until it is closed.
+pub fn run() -> Result<String, String> {
+    let mut terminal = setup().map_err(|e| e.to_string())?;
+    let result = event_loop(&mut terminal);
+
+    disable_raw_mode().map_err(|e| e.to_string())?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen).map_err(|e| e.to_string())?;
+    terminal.show_cursor().map_err(|e| e.to_string())?;
+
+    result.map(|_| String::new()).map_err(|e| e.to_string())
+}
+
+fn setup() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+
+    Terminal::new(CrosstermBackend::new(stdout))
+}
+
+fn event_loop<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    let mut form = Form::new();
+
+    loop {
+        terminal.draw(|f| form.draw(f))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Release && form.key(key) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use crate::calc::field::Field::{Current, InputPower, Voltage};
+    use crate::calc::number::Num;
+    use crate::cli::tui::Form;
+
+    fn press(form: &mut Form, code: KeyCode) -> bool {
+        form.key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_form() {
+        let mut form = Form::new();
+
+        press(&mut form, KeyCode::Tab);
+        for c in "12".chars() {
+            press(&mut form, KeyCode::Char(c));
+        }
+        press(&mut form, KeyCode::Tab);
+        for c in "2.55".chars() {
+            press(&mut form, KeyCode::Char(c));
+        }
+        press(&mut form, KeyCode::Backspace);
+
+        assert_eq!(form.calc[Voltage], Num::In(12.0));
+        assert_eq!(form.calc[Current], Num::In(2.5));
+        assert_eq!(form.calc[InputPower], Num::Out(30.0));
+
+        press(&mut form, KeyCode::BackTab);
+        press(&mut form, KeyCode::BackTab);
+        assert!(form.is_disabled(InputPower));
+        press(&mut form, KeyCode::Char('1'));
+        assert_eq!(form.calc[InputPower], Num::Out(30.0));
+
+        press(&mut form, KeyCode::Tab);
+        press(&mut form, KeyCode::Delete);
+        assert_eq!(form.calc[InputPower], Num::None);
+        assert!(press(&mut form, KeyCode::Esc));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut form = Form::new();
+        form.edit(Voltage, "12".to_string());
+        form.edit(Current, "2.5".to_string());
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|f| form.draw(f)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| (0..100).map(|x| buffer.get(x, y).symbol.clone()).collect::<String>();
+        assert!(line(7).contains("P_In [W]"));
+        assert!(line(8).contains("30.00"));
+        assert!(line(11).contains("12"));
+    }
+}
@@ -1,20 +1,117 @@
+use std::fmt;
+
+use crate::calc::field::Field;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ErrorKind {
     Overconstrained,
     Underconstrained,
+    Format,
+    Version,
+    Unreachable,
+    /// A value had to be divided by zero, like the voltage calculated from a current of zero.
+    DivisionByZero,
+    /// The result is infinite or not a number.
+    NotFinite,
+    /// The parameters of a request don't fit together, like the same field varied on both axes
+    /// of a grid.
+    InvalidInput,
 }
 
-/// A structure able to represent errors resulting from solving equations.
-#[derive(Copy, Clone, Debug)]
+/// A structure able to represent errors resulting from solving equations or loading files. Errors
+/// of relations name the relation, the fields involved and their values.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Error {
     pub kind: ErrorKind,
     pub description: &'static str,
+    /// The fields involved.
+    pub fields: Vec<Field>,
+    /// The relation involved, formatted like `u · i = p_in`.
+    pub relation: Option<String>,
+    /// The values of the fields involved that are known.
+    pub values: Vec<(Field, f64)>,
+    /// The difference of both sides of the relation.
+    pub residual: Option<f64>,
 }
 
 impl Error {
     pub fn new(kind: ErrorKind, description: &'static str) -> Self {
-        Self { kind, description }
+        Self {
+            kind,
+            description,
+            fields: Vec::new(),
+            relation: None,
+            values: Vec::new(),
+            residual: None,
+        }
+    }
+
+    pub fn with_fields(mut self, fields: Vec<Field>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub fn with_relation(mut self, relation: impl ToString) -> Self {
+        self.relation = Some(relation.to_string());
+        self
+    }
+
+    pub fn with_values(mut self, values: Vec<(Field, f64)>) -> Self {
+        self.values = values;
+        self
+    }
+
+    pub fn with_residual(mut self, residual: f64) -> Self {
+        self.residual = Some(residual);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)?;
+
+        if let Some(r) = &self.relation {
+            write!(f, " in {}", r)?;
+        }
+        if !self.fields.is_empty() {
+            let fields = self.fields.iter().map(|f| f.id()).collect::<Vec<&str>>();
+            write!(f, " (fields: {})", fields.join(", "))?;
+        }
+        if !self.values.is_empty() {
+            let values = self.values.iter().map(|(field, v)| format!("{} = {}", field.id(), v)).collect::<Vec<String>>();
+            write!(f, " with {}", values.join(", "))?;
+        }
+        if let Some(r) = self.residual {
+            write!(f, ", residual {:e}", r)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod test {
+    use crate::calc::field::Field::{Current, InputPower, Voltage};
+    use crate::error::Error;
+    use crate::error::ErrorKind::{Format, Overconstrained};
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Error::new(Format, "Unknown field").to_string(), "Unknown field");
+
+        let error = Error::new(Overconstrained, "The values contradict each other")
+            .with_relation("u · i = p_in")
+            .with_fields(vec![Voltage, Current, InputPower])
+            .with_values(vec![(Voltage, 12.0), (Current, 2.5), (InputPower, 31.0)])
+            .with_residual(-1.0);
+        assert_eq!(
+            error.to_string(),
+            "The values contradict each other in u · i = p_in (fields: u, i, p_in) with u = 12, i = 2.5, p_in = 31, residual -1e0",
+        );
     }
 }
@@ -0,0 +1,1243 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use stdweb::traits::{IEvent, IKeyboardEvent};
+use stdweb::web::event::KeyDownEvent;
+use yew::format::Json;
+use yew::prelude::*;
+use yew::services::storage::{Area, StorageService};
+use yew::services::timeout::{TimeoutService, TimeoutTask};
+
+use crate::calc::calculation::Calculation;
+use crate::calc::compare;
+use crate::calc::field::Field;
+use crate::calc::goal::Goal;
+use crate::calc::history::History;
+use crate::calc::newton::Report;
+use crate::calc::number::Num;
+use crate::calc::query;
+use crate::calc::reconcile::{self, Residual};
+use crate::calc::sensitivity::{self, Matrix};
+use crate::calc::sweep::{self, Grid, Sweep};
+use crate::calc::validation::{self, Issue, Severity};
+use crate::catalogue::motor::Motor;
+use crate::catalogue::ratio::{Load, Objective, best_available_ratio, optimal_ratio, parse_ratios};
+use crate::error::ErrorKind;
+
+/// The local storage key of the inputs of the current session.
+const SESSION_KEY: &str = "motorcalc.session";
+/// The local storage key of the saved projects.
+const PROJECTS_KEY: &str = "motorcalc.projects";
+/// The distance between two columns and two rows of the form and the distance of the first row
+/// from the top in pixels.
+const COLUMN_WIDTH: u16 = 100;
+const ROW_HEIGHT: u16 = 80;
+const TOP: u16 = 40;
+/// The width of a field of the form in pixels.
+const FIELD_WIDTH: u16 = 160;
+/// The size of the sweep chart in pixels.
+const CHART_WIDTH: f64 = 480.0;
+const CHART_HEIGHT: f64 = 240.0;
+/// The maximum number of values of a sweep and of each axis of a grid, so that a large number
+/// can't freeze the page.
+const MAX_STEPS: usize = 200;
+const MAX_GRID_STEPS: usize = 40;
+/// The time without edits after which the sweep is solved again.
+const SWEEP_DELAY: Duration = Duration::from_millis(300);
+/// The colors of the lines of the sweep chart.
+const CHART_COLORS: [&str; 6] = ["#c85742", "#4a9fc8", "#7bc842", "#c8a642", "#a642c8", "#dfdfdf"];
+
+#[derive(Debug)]
+pub struct Model {
+    pub link: ComponentLink<Self>,
+    pub timeout: TimeoutService,
+    /// The pending solve of the sweep, replaced by every edit.
+    pub sweep_task: Option<TimeoutTask>,
+    pub calc: Calculation,
+    /// The report of the simultaneous solve, if the relations couldn't be solved one after
+    /// another.
+    pub report: Option<Report>,
+    /// All physically valid solutions if the calculation has more than one.
+    pub solutions: Vec<Calculation>,
+    /// The index of the solution the calculation continues with.
+    pub branch: usize,
+    /// The violated constraints of the fields.
+    pub issues: Vec<Issue>,
+    /// The sensitivities of the outputs to the inputs, if there are both.
+    pub sensitivity: Option<Matrix>,
+    /// The scenarios the current calculation is compared to.
+    pub scenarios: Vec<Calculation>,
+    /// The text of the input fields.
+    pub inputs: HashMap<Field, String>,
+    pub history: History,
+    /// The field that was edited last. Consecutive edits of the same field are undone at once.
+    pub last_edit: Option<Field>,
+    pub ratio: RatioForm,
+    pub sweep: SweepForm,
+    pub goal: GoalForm,
+    /// The tolerances of the measured inputs used for reconciliation.
+    pub tolerances: HashMap<Field, f64>,
+    /// The deviations of the measured inputs from the last reconciliation.
+    pub residuals: Vec<Residual>,
+    pub projects: Vec<Project>,
+    /// The text of the project name input.
+    pub project_name: String,
+    pub significant_figures: usize,
+}
+
+/// A named calculation saved in the local storage.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    pub calc: Calculation,
+}
+
+/// The motor and load data needed to optimize the transmission ratio that isn't part of the
+/// calculation.
+#[derive(Clone, Debug)]
+pub struct RatioForm {
+    /// Torque constant: k<sub>M</sub> [Nm/A]
+    pub k_m: Num,
+    /// No load current: I<sub>0</sub> [A]
+    pub i_0: Num,
+    /// Rotor inertia: J<sub>M</sub> [kgm²]
+    pub j_m: Num,
+    /// Load inertia: J<sub>T</sub> [kgm²]
+    pub j_t: Num,
+    /// The available transmission ratios.
+    pub ratios: Vec<f64>,
+    pub objective: Objective,
+}
+
+/// The range an input is varied over and the outputs that are shown for every value.
+#[derive(Clone, Debug)]
+pub struct SweepForm {
+    /// The varied field.
+    pub field: Field,
+    pub from: Num,
+    pub to: Num,
+    pub steps: usize,
+    /// The fields that are tabulated and plotted.
+    pub outputs: Vec<Field>,
+    /// The second varied field, if two fields are varied simultaneously.
+    pub y_field: Option<Field>,
+    pub y_from: Num,
+    pub y_to: Num,
+    pub y_steps: usize,
+    /// The field shown in the heatmap.
+    pub map: Field,
+    /// The solved calculations of the sweep, empty if the range is incomplete.
+    pub results: Vec<Calculation>,
+    /// The grid of both varied inputs and its solved rows, if the ranges are complete.
+    pub grid: Option<(Grid, Vec<Vec<Calculation>>)>,
+}
+
+/// The output that should reach a target when solving for an input.
+#[derive(Clone, Debug)]
+pub struct GoalForm {
+    pub output: Field,
+    pub target: Num,
+    /// The reason why the last goal seek failed.
+    pub error: Option<String>,
+}
+
+/// An enum representing the messages sent from the UI.
+#[derive(Clone, Debug)]
+pub enum Msg {
+    Calc(Field, String),
+    Ratio(&'static str, String),
+    RatioObjective(Objective),
+    AcceptRatio,
+    Branch(usize),
+    SweepField(Field),
+    Sweep(&'static str, String),
+    RunSweep,
+    SweepOutput(Field),
+    SweepY(Option<Field>),
+    SweepMap(Field),
+    GoalOutput(Field),
+    GoalTarget(String),
+    SolveFor(Field),
+    Tolerance(Field, String),
+    Reconcile,
+    ProjectName(String),
+    SaveProject,
+    OpenProject(usize),
+    RenameProject(usize),
+    DuplicateProject(usize),
+    DeleteProject(usize),
+    Undo,
+    Redo,
+    CloneScenario,
+    EditScenario(usize),
+    RemoveScenario(usize),
+    Ignore,
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut model = Self {
+            link,
+            timeout: TimeoutService::new(),
+            sweep_task: None,
+            calc: Calculation::new(),
+            report: None,
+            solutions: Vec::new(),
+            branch: 0,
+            issues: Vec::new(),
+            sensitivity: None,
+            scenarios: Vec::new(),
+            inputs: HashMap::new(),
+            history: History::new(),
+            last_edit: None,
+            ratio: RatioForm {
+                k_m: Num::None,
+                i_0: Num::None,
+                j_m: Num::None,
+                j_t: Num::None,
+                ratios: Vec::new(),
+                objective: Objective::Efficiency,
+            },
+            sweep: SweepForm {
+                field: Field::Voltage,
+                from: Num::None,
+                to: Num::None,
+                steps: 10,
+                outputs: Vec::new(),
+                y_field: None,
+                y_from: Num::None,
+                y_to: Num::None,
+                y_steps: 10,
+                map: Field::MotorEfficiency,
+                results: Vec::new(),
+                grid: None,
+            },
+            goal: GoalForm {
+                output: Field::TransmissionEfficiency,
+                target: Num::None,
+                error: None,
+            },
+            tolerances: HashMap::new(),
+            residuals: Vec::new(),
+            projects: Vec::new(),
+            project_name: String::new(),
+            significant_figures: 10,
+        };
+
+        if let Some(storage) = Self::storage() {
+            if let Json(Ok(projects)) = storage.restore(PROJECTS_KEY) {
+                model.projects = projects;
+            }
+
+            if let Json(Ok(calc)) = storage.restore(SESSION_KEY) {
+                model.load(calc);
+            }
+        }
+
+        if let Some(calc) = Self::url_calculation() {
+            model.load(calc);
+        }
+
+        model
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Calc(field, s) => {
+                if self.last_edit != Some(field) {
+                    self.history.push(self.calc);
+                    self.last_edit = Some(field);
+                }
+
+                *self.calc.get_mut(field) = field.parse(s.clone());
+                self.inputs.insert(field, s);
+                self.solve();
+            }
+            Msg::Ratio(id, s) => {
+                match id {
+                    "k_m" => self.ratio.k_m = Num::parse(s),
+                    "i_0" => self.ratio.i_0 = Num::parse(s),
+                    "j_m" => self.ratio.j_m = Num::parse(s),
+                    "j_t" => self.ratio.j_t = Num::parse(s),
+                    "ratios" => self.ratio.ratios = parse_ratios(&s),
+                    _ => (),
+                }
+            }
+            Msg::RatioObjective(objective) => self.ratio.objective = objective,
+            Msg::AcceptRatio => {
+                if let Some(r) = self.suggested_ratio() {
+                    self.record();
+                    self.calc[Field::TransmissionRatio] = Num::In(r);
+                    self.inputs.insert(Field::TransmissionRatio, Field::TransmissionRatio.format(Num::In(r)));
+                    self.solve();
+                }
+            }
+            Msg::Branch(index) => {
+                if index != self.branch && index < self.solutions.len() {
+                    self.record();
+                    self.calc = self.solutions[index];
+                    self.solve();
+                }
+            }
+            Msg::SweepField(field) => {
+                self.sweep.field = field;
+                self.run_sweep();
+            }
+            Msg::Sweep(id, s) => {
+                match id {
+                    "from" => self.sweep.from = Num::parse(s),
+                    "to" => self.sweep.to = Num::parse(s),
+                    "steps" => self.sweep.steps = s.trim().parse::<usize>().unwrap_or(0).min(MAX_STEPS),
+                    "y_from" => self.sweep.y_from = Num::parse(s),
+                    "y_to" => self.sweep.y_to = Num::parse(s),
+                    "y_steps" => self.sweep.y_steps = s.trim().parse::<usize>().unwrap_or(0).min(MAX_GRID_STEPS),
+                    _ => (),
+                }
+                self.schedule_sweep();
+            }
+            Msg::RunSweep => {
+                self.sweep_task = None;
+                self.run_sweep();
+            }
+            Msg::SweepOutput(field) => {
+                match self.sweep.outputs.iter().position(|o| *o == field) {
+                    Some(i) => {
+                        self.sweep.outputs.remove(i);
+                    }
+                    None => self.sweep.outputs.push(field),
+                }
+            }
+            Msg::SweepY(field) => {
+                self.sweep.y_field = field;
+                self.run_sweep();
+            }
+            Msg::SweepMap(field) => self.sweep.map = field,
+            Msg::GoalOutput(field) => self.goal.output = field,
+            Msg::GoalTarget(s) => {
+                self.goal.target = Num::parse(s);
+                self.goal.error = None;
+            }
+            Msg::SolveFor(field) => {
+                if let Some(target) = self.goal.target.as_option() {
+                    match Goal::new(field, self.goal.output, target).seek(&self.calc) {
+                        Ok(calc) => {
+                            self.goal.error = None;
+                            self.record();
+                            self.load(calc);
+                        }
+                        Err(e) => self.goal.error = Some(e.to_string()),
+                    }
+                }
+            }
+            Msg::Tolerance(field, s) => {
+                match Num::parse(s).as_option() {
+                    Some(t) => self.tolerances.insert(field, t),
+                    None => self.tolerances.remove(&field),
+                };
+            }
+            Msg::Reconcile => {
+                if let Ok(r) = reconcile::reconcile(&self.calc, &self.tolerances) {
+                    self.record();
+                    self.load(r.calc);
+                    self.residuals = r.residuals;
+                }
+            }
+            Msg::ProjectName(name) => {
+                self.project_name = name;
+                return false;
+            }
+            Msg::SaveProject => {
+                let name = match self.project_name.trim() {
+                    "" => format!("Project {}", self.projects.len() + 1),
+                    n => n.to_string(),
+                };
+                self.projects.push(Project { name, calc: self.calc });
+                self.store_projects();
+            }
+            Msg::OpenProject(index) => {
+                if let Some(calc) = self.projects.get(index).map(|p| p.calc) {
+                    self.record();
+                    self.load(calc);
+                }
+            }
+            Msg::RenameProject(index) => {
+                let name = self.project_name.trim().to_string();
+                if let (Some(p), false) = (self.projects.get_mut(index), name.is_empty()) {
+                    p.name = name;
+                    self.store_projects();
+                }
+            }
+            Msg::DuplicateProject(index) => {
+                if let Some(p) = self.projects.get(index) {
+                    let copy = Project { name: format!("{} (copy)", p.name), calc: p.calc };
+                    self.projects.insert(index + 1, copy);
+                    self.store_projects();
+                }
+            }
+            Msg::DeleteProject(index) => {
+                if index < self.projects.len() {
+                    self.projects.remove(index);
+                    self.store_projects();
+                }
+            }
+            Msg::Undo => {
+                if let Some(calc) = self.history.undo(self.calc) {
+                    self.last_edit = None;
+                    self.load(calc);
+                }
+            }
+            Msg::Redo => {
+                if let Some(calc) = self.history.redo(self.calc) {
+                    self.last_edit = None;
+                    self.load(calc);
+                }
+            }
+            Msg::CloneScenario => self.scenarios.push(self.calc),
+            Msg::EditScenario(index) => {
+                if let Some(calc) = self.scenarios.get(index).copied() {
+                    self.record();
+                    self.scenarios[index] = self.calc;
+                    self.load(calc);
+                }
+            }
+            Msg::RemoveScenario(index) => {
+                if index < self.scenarios.len() {
+                    self.scenarios.remove(index);
+                }
+            }
+            Msg::Ignore => return false,
+        }
+
+        true
+    }
+
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class="motorcalc" onkeydown=self.link.callback(Self::shortcut)>
+                <header class="heading">
+                    <h1>{ "motorcalc" }</h1>
+                    <button onclick=self.link.callback(|_| Msg::Undo)
+                        title="Undo (Ctrl+Z)"
+                        disabled={ !self.history.can_undo() }>{ "Undo" }</button>
+                    <button onclick=self.link.callback(|_| Msg::Redo)
+                        title="Redo (Ctrl+Shift+Z)"
+                        disabled={ !self.history.can_redo() }>{ "Redo" }</button>
+                    <a class="github" href="https://github.com/saecki/motorcalc.git">{ "GitHub" }</a>
+                </header>
+                <div class="calc" style={ Self::form_size() }>
+                    { for Field::ALL.iter().map(|f| self.field(*f)) }
+                </div>
+                { match self.report {
+                    Some(r) => html! { <p class="report">{ r.description() }</p> },
+                    None => html! {},
+                } }
+                { self.branch_panel() }
+                { self.comparison_panel() }
+                { self.sensitivity_panel() }
+                { self.ratio_panel() }
+                { self.goal_panel() }
+                { self.reconcile_panel() }
+                { self.sweep_panel() }
+                { self.projects_panel() }
+            </div>
+        }
+    }
+}
+
+impl Model {
+    /// Replaces the calculation and the text of the input fields with the inputs of the calculation.
+    pub fn load(&mut self, calc: Calculation) {
+        self.calc = calc;
+        self.inputs.clear();
+
+        for f in Field::ALL.iter() {
+            let num = calc.get(*f);
+            if num.is_input() {
+                self.inputs.insert(*f, f.format(num));
+            }
+        }
+
+        self.solve();
+    }
+
+    /// Fills the missing values of the calculation and stores its inputs in the url, so that the
+    /// link reproduces the calculation, and in the local storage, so that reloading the page
+    /// restores it. If there is more than one solution the calculation continues with the chosen
+    /// branch. A calculation that is one of the solutions, like one restored by undo, chooses its
+    /// own branch and a change of the number of solutions resets it to the first one. The issues,
+    /// the sensitivities and the sweep are updated and the residuals of a previous reconciliation
+    /// are discarded.
+    fn solve(&mut self) {
+        let (calc, report) = self.calc.solve();
+        let solutions = self.calc.solutions();
+        if let Some(i) = solutions.iter().position(|s| *s == self.calc) {
+            self.branch = i;
+        } else if solutions.len() != self.solutions.len() {
+            self.branch = 0;
+        }
+        self.solutions = solutions;
+        self.calc = match self.solutions.len() {
+            0 | 1 => calc,
+            n => self.solutions[self.branch.min(n - 1)],
+        };
+        self.report = report;
+        self.issues = validation::validate(&self.calc);
+        self.sensitivity = Some(sensitivity::sensitivities(&self.calc))
+            .filter(|m| !m.inputs.is_empty() && !m.outputs.is_empty());
+        self.residuals.clear();
+        self.schedule_sweep();
+
+        let url = format!("#{}", query::encode(&self.calc));
+        js! { @(no_return) window.history.replaceState(null, "", @{url}); }
+
+        if let Some(mut storage) = Self::storage() {
+            storage.store(SESSION_KEY, Json(&self.calc));
+        }
+    }
+
+    /// Solves the sweep once there was no edit for SWEEP_DELAY, so that typing doesn't solve it
+    /// for every keystroke. Without a complete range there is nothing to solve and it's cleared
+    /// right away.
+    fn schedule_sweep(&mut self) {
+        if self.sweep.from.is_none() || self.sweep.to.is_none() {
+            self.sweep_task = None;
+            self.run_sweep();
+        } else {
+            self.sweep_task = Some(self.timeout.spawn(SWEEP_DELAY, self.link.callback(|_| Msg::RunSweep)));
+        }
+    }
+
+    /// Solves the sweep and its grid again.
+    fn run_sweep(&mut self) {
+        self.sweep.results = self.sweep_results();
+        self.sweep.grid = self.sweep_grid().and_then(|g| g.run(&self.calc).ok().map(|r| (g, r)));
+    }
+
+    /// Records the calculation before a change that replaces more than a single field.
+    fn record(&mut self) {
+        self.history.push(self.calc);
+        self.last_edit = None;
+    }
+
+    /// Maps the keyboard shortcuts for undo and redo to their messages.
+    fn shortcut(e: KeyDownEvent) -> Msg {
+        if !(e.ctrl_key() || e.meta_key()) {
+            return Msg::Ignore;
+        }
+
+        let msg = match e.key().to_lowercase().as_str() {
+            "z" if e.shift_key() => Msg::Redo,
+            "z" => Msg::Undo,
+            "y" => Msg::Redo,
+            _ => return Msg::Ignore,
+        };
+        e.prevent_default();
+
+        msg
+    }
+
+    /// Stores the projects in the local storage.
+    fn store_projects(&self) {
+        if let Some(mut storage) = Self::storage() {
+            storage.store(PROJECTS_KEY, Json(&self.projects));
+        }
+    }
+
+    /// Returns the local storage if it's available.
+    fn storage() -> Option<StorageService> {
+        StorageService::new(Area::Local).ok()
+    }
+
+    /// Returns the calculation encoded in the url if there is one.
+    fn url_calculation() -> Option<Calculation> {
+        let hash = yew::utils::window().location()?.hash().ok()?;
+
+        if hash.len() > 1 {
+            Some(query::decode(&hash))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the size of the form as a style, so that the grid positions of all fields fit.
+    fn form_size() -> String {
+        let (columns, rows) = Field::ALL.iter()
+            .map(|f| f.position())
+            .fold((0, 0), |(c, r), (column, row)| (c.max(column), r.max(row)));
+
+        format!("width: {}px; height: {}px", columns * COLUMN_WIDTH + FIELD_WIDTH, TOP + (rows + 1) * ROW_HEIGHT)
+    }
+
+    /// Returns html representing an input field it's label and a output text span.
+    pub fn field(&self, field: Field) -> Html {
+        let id = field.id();
+        let num = self.calc.get(field);
+        let issues = validation::issues_of(&self.issues, field);
+        let class = if issues.iter().any(|i| i.severity == Severity::Error) {
+            format!("{} invalid", id)
+        } else if !issues.is_empty() {
+            format!("{} unusual", id)
+        } else {
+            id.to_string()
+        };
+
+        let (column, row) = field.position();
+        let style = format!("left: {}px; top: {}px", column * COLUMN_WIDTH, TOP + row * ROW_HEIGHT);
+
+        html! {
+            <div class={ class } style={ style }>
+                <label for={ id }
+                    title={ field.description() }>
+                    { Self::label(field) }
+                </label>
+                <div class="input-output">
+                    <input class="edit"
+                        type="text"
+                        id={ id }
+                        value={ self.inputs.get(&field).cloned().unwrap_or_default() }
+                        oninput=self.link.callback(move |e: InputData| Msg::Calc(field, e.value))
+                        disabled={ num.is_output() }
+                        />
+                    <span class="display">{ if num.is_output() { field.display(num, self.significant_figures) } else { "".into() } }</span>
+                    <button class="solve-for"
+                        title="Solve for this input so that the goal is reached"
+                        onclick=self.link.callback(move |_| Msg::SolveFor(field))
+                        disabled={ !num.is_input() || self.goal.target.is_none() || field == self.goal.output }>
+                        { "⇄" }
+                    </button>
+                </div>
+                { for issues.iter().map(|i| html! {
+                    <p class={ match i.severity { Severity::Error => "issue error", Severity::Warning => "issue warning" } }>
+                        { i.message }
+                    </p>
+                }) }
+            </div>
+        }
+    }
+
+    /// Returns html representing the symbol and the unit of the field.
+    fn label(field: Field) -> Html {
+        html! {
+            <>
+                { field.symbol() }<sub>{ field.sub() }</sub>{ if field.unit().is_empty() { "".into() } else { format!(" [{}]", field.unit()) } }
+            </>
+        }
+    }
+
+    /// Returns html representing the comparison of the current calculation to the scenarios.
+    pub fn comparison_panel(&self) -> Html {
+        let table = if self.scenarios.is_empty() {
+            html! {}
+        } else {
+            html! {
+                <table class="comparison">
+                    <tr>
+                        <th></th>
+                        <th>{ "Current" }</th>
+                        { for (0..self.scenarios.len()).map(|i| html! {
+                            <th>
+                                { format!("Scenario {}", i + 1) }
+                                <button title="Edit this scenario"
+                                    onclick=self.link.callback(move |_| Msg::EditScenario(i))>{ "Edit" }</button>
+                                <button title="Remove this scenario"
+                                    onclick=self.link.callback(move |_| Msg::RemoveScenario(i))>{ "Remove" }</button>
+                            </th>
+                        }) }
+                    </tr>
+                    { for Field::ALL.iter().map(|f| self.comparison_row(*f)) }
+                </table>
+            }
+        };
+
+        html! {
+            <div class="panel">
+                <h2>{ "Scenarios" }</h2>
+                <button onclick=self.link.callback(|_| Msg::CloneScenario)>{ "Clone current scenario" }</button>
+                { table }
+            </div>
+        }
+    }
+
+    /// Returns html representing the operating points if the calculation has more than one
+    /// solution, like a low current and a high current one, and the choice between them. Only the
+    /// fields that differ between the solutions are shown.
+    pub fn branch_panel(&self) -> Html {
+        if self.solutions.len() < 2 {
+            return html! {};
+        }
+
+        let differs = |f: &Field| self.solutions.iter().any(|c| c.get(*f).as_option() != self.solutions[0].get(*f).as_option());
+
+        html! {
+            <div class="panel">
+                <h2>{ "Operating points" }</h2>
+                <p>{ "The inputs are met by more than one operating point, choose the one to continue with." }</p>
+                <table class="branches">
+                    <tr>
+                        <th></th>
+                        { for (0..self.solutions.len()).map(|i| html! {
+                            <th>
+                                <input type="radio"
+                                    name="branch"
+                                    id={ format!("branch_{}", i) }
+                                    checked={ i == self.branch.min(self.solutions.len() - 1) }
+                                    onchange=self.link.callback(move |_| Msg::Branch(i))
+                                    />
+                                <label for={ format!("branch_{}", i) }>{ format!("Solution {}", i + 1) }</label>
+                            </th>
+                        }) }
+                    </tr>
+                    { for Field::ALL.iter().copied().filter(differs).map(|f| html! {
+                        <tr>
+                            <td title={ f.description() }>{ Self::label(f) }</td>
+                            { for self.solutions.iter().map(|c| html! {
+                                <td>{ f.display(c.get(f), self.significant_figures) }</td>
+                            }) }
+                        </tr>
+                    }) }
+                </table>
+            </div>
+        }
+    }
+
+    /// Returns html representing a field of all scenarios and their difference to the current
+    /// calculation.
+    fn comparison_row(&self, field: Field) -> Html {
+        let base = self.calc.get(field);
+
+        html! {
+            <tr>
+                <td title={ field.description() }>{ Self::label(field) }</td>
+                <td>{ field.display(base, self.significant_figures) }</td>
+                { for self.scenarios.iter().map(|s| {
+                    let num = s.get(field);
+                    let diff = compare::difference(base, num);
+                    let class = match diff {
+                        Some(d) if d > 0.05 => "increase",
+                        Some(d) if d < -0.05 => "decrease",
+                        _ => "",
+                    };
+
+                    html! {
+                        <td class={ class }>
+                            { field.display(num, self.significant_figures) }
+                            { diff.map(|d| format!(" ({:+.1} %)", d)).unwrap_or_default() }
+                        </td>
+                    }
+                }) }
+            </tr>
+        }
+    }
+
+    /// Returns html representing the relative sensitivity of every output to every input, which
+    /// shows the inputs whose tolerances matter most.
+    pub fn sensitivity_panel(&self) -> Html {
+        let matrix = match &self.sensitivity {
+            Some(m) => m,
+            None => return html! {},
+        };
+
+        html! {
+            <div class="panel">
+                <h2 title="The change of an output in % caused by a change of an input by 1 %">{ "Sensitivity" }</h2>
+                <table class="sensitivity">
+                    <tr>
+                        <th></th>
+                        { for matrix.inputs.iter().map(|f| html! {
+                            <th title={ f.description() }>{ Self::label(*f) }</th>
+                        }) }
+                    </tr>
+                    { for matrix.outputs.iter().map(|f| html! {
+                        <tr>
+                            <td title={ f.description() }>{ Self::label(*f) }</td>
+                            { for matrix.inputs.iter().map(|input| {
+                                let e = matrix.elasticity(&self.calc, *f, *input);
+                                let class = match e.map(f64::abs) {
+                                    Some(e) if e >= 0.5 => "high",
+                                    Some(e) if e >= 0.05 => "medium",
+                                    _ => "low",
+                                };
+
+                                html! {
+                                    <td class={ class }>{ e.map(|e| format!("{:+.2}", e)).unwrap_or_default() }</td>
+                                }
+                            }) }
+                        </tr>
+                    }) }
+                </table>
+            </div>
+        }
+    }
+
+    /// Returns html representing the saved projects.
+    pub fn projects_panel(&self) -> Html {
+        html! {
+            <aside class="projects">
+                <h2>{ "Projects" }</h2>
+                <input type="text"
+                    placeholder="Name"
+                    value={ self.project_name.clone() }
+                    oninput=self.link.callback(|e: InputData| Msg::ProjectName(e.value))
+                    />
+                <button onclick=self.link.callback(|_| Msg::SaveProject)>{ "Save" }</button>
+                <ul>
+                    { for self.projects.iter().enumerate().map(|(i, p)| self.project(i, p)) }
+                </ul>
+            </aside>
+        }
+    }
+
+    /// Returns html representing a saved project and the actions that can be applied to it.
+    fn project(&self, index: usize, project: &Project) -> Html {
+        html! {
+            <li>
+                <a title="Open" onclick=self.link.callback(move |_| Msg::OpenProject(index))>{ &project.name }</a>
+                <button title="Rename to the entered name"
+                    onclick=self.link.callback(move |_| Msg::RenameProject(index))>{ "Rename" }</button>
+                <button onclick=self.link.callback(move |_| Msg::DuplicateProject(index))>{ "Duplicate" }</button>
+                <button onclick=self.link.callback(move |_| Msg::DeleteProject(index))>{ "Delete" }</button>
+            </li>
+        }
+    }
+
+    /// Returns html representing the transmission ratio optimization.
+    pub fn ratio_panel(&self) -> Html {
+        let suggestion = self.suggested_ratio();
+
+        html! {
+            <div class="panel">
+                <h2>{ "Transmission ratio" }</h2>
+                { self.ratio_field("k_m", "k", "M", "Nm/A", "Torque constant") }
+                { self.ratio_field("i_0", "I", "0", "A", "No load current") }
+                { self.ratio_field("j_m", "J", "M", "kgm²", "Rotor inertia") }
+                { self.ratio_field("j_t", "J", "T", "kgm²", "Load inertia") }
+                { self.ratio_field("ratios", "i", "available", "", "Available transmission ratios separated by commas") }
+                <div class="field">
+                    <label for="objective" title="Optimization objective">{ "Optimize for" }</label>
+                    <select id="objective"
+                        onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(s) => match s.value().as_deref() {
+                                Some("current") => Msg::RatioObjective(Objective::Current),
+                                Some("inertia") => Msg::RatioObjective(Objective::Inertia),
+                                _ => Msg::RatioObjective(Objective::Efficiency),
+                            },
+                            _ => Msg::RatioObjective(Objective::Efficiency),
+                        })>
+                        <option value="efficiency">{ "Efficiency" }</option>
+                        <option value="current">{ "Current" }</option>
+                        <option value="inertia">{ "Acceleration" }</option>
+                    </select>
+                </div>
+                <div class="field">
+                    <label title="Optimal transmission ratio">{ "i" }<sub>{ "opt" }</sub></label>
+                    <span>{ suggestion.map(|r| Num::Out(r).display_ratio()).unwrap_or_default() }</span>
+                    <button onclick=self.link.callback(|_| Msg::AcceptRatio)
+                        disabled={ suggestion.is_none() }>
+                        { "Accept" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    /// Returns html representing an input field of the transmission ratio optimization.
+    fn ratio_field(&self, id: &'static str, label: &str, sub_label: &str, unit: &str, description: &str) -> Html {
+        html! {
+            <div class="field">
+                <label for={ id }
+                    title={ description }>
+                    { label }<sub>{ sub_label }</sub>{ if unit.len() == 0 { "".into() } else { format!(" [{}]", unit) } }
+                </label>
+                <input type="text"
+                    id={ id }
+                    oninput=self.link.callback(move |e: InputData| Msg::Ratio(id, e.value))
+                    />
+            </div>
+        }
+    }
+
+    /// Returns html representing the target output of the goal seek. The input that is varied is
+    /// chosen by the solve for button of a field.
+    pub fn goal_panel(&self) -> Html {
+        html! {
+            <div class="panel">
+                <h2>{ "Goal seek" }</h2>
+                <div class="field">
+                    <label for="goal_output" title="The output that should reach the target">{ "Output" }</label>
+                    <select id="goal_output"
+                        onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(s) => s.value()
+                                .and_then(|v| Field::from_id(&v))
+                                .map_or(Msg::Ignore, Msg::GoalOutput),
+                            _ => Msg::Ignore,
+                        })>
+                        { for Field::ALL.iter().map(|f| html! {
+                            <option value={ f.id() } selected={ *f == self.goal.output }>{ f.description() }</option>
+                        }) }
+                    </select>
+                </div>
+                <div class="field">
+                    <label for="goal_target">{ "Target" }</label>
+                    <input type="text"
+                        id="goal_target"
+                        oninput=self.link.callback(|e: InputData| Msg::GoalTarget(e.value))
+                        />
+                </div>
+                <p>{ "Press ⇄ next to an input to solve for it." }</p>
+                { match &self.goal.error {
+                    Some(e) => html! { <p class="error">{ e }</p> },
+                    None => html! {},
+                } }
+            </div>
+        }
+    }
+
+    /// Returns html representing the tolerances of the measured inputs and their deviations from
+    /// the last reconciliation. Only shown if the inputs contradict each other or were reconciled.
+    pub fn reconcile_panel(&self) -> Html {
+        let contradicting = matches!(self.calc.check(), Err(e) if matches!(e.kind, ErrorKind::Overconstrained));
+        if !contradicting && self.residuals.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <div class="panel">
+                <h2>{ "Reconciliation" }</h2>
+                <p>{ format!("The inputs are weighted by their tolerance, {} % of the value by default.", reconcile::DEFAULT_RELATIVE_TOLERANCE * 100.0) }</p>
+                <table class="residuals">
+                    <tr>
+                        <th></th>
+                        <th>{ "Tolerance" }</th>
+                        <th>{ "Measured" }</th>
+                        <th>{ "Reconciled" }</th>
+                        <th title="The deviation relative to the tolerance">{ "Residual" }</th>
+                    </tr>
+                    { for Field::ALL.iter().copied().filter(|f| self.calc.get(*f).is_input()).map(|f| {
+                        let residual = self.residuals.iter().find(|r| r.field == f);
+
+                        html! {
+                            <tr>
+                                <td title={ f.description() }>{ Self::label(f) }</td>
+                                <td>
+                                    <input type="text"
+                                        value={ self.tolerances.get(&f).map(|t| t.to_string()).unwrap_or_default() }
+                                        oninput=self.link.callback(move |e: InputData| Msg::Tolerance(f, e.value))
+                                        />
+                                </td>
+                                <td>{ residual.map(|r| f.display(Num::In(r.measured), self.significant_figures)).unwrap_or_default() }</td>
+                                <td>{ residual.map(|r| f.display(Num::In(r.reconciled), self.significant_figures)).unwrap_or_default() }</td>
+                                <td>{ residual.map(|r| format!("{:+.2}", r.normalized)).unwrap_or_default() }</td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+                <button onclick=self.link.callback(|_| Msg::Reconcile)
+                    disabled={ !contradicting }>{ "Reconcile" }</button>
+            </div>
+        }
+    }
+
+    /// Returns html representing the parameter sweep with a table and a chart of the selected
+    /// outputs.
+    pub fn sweep_panel(&self) -> Html {
+        html! {
+            <div class="panel sweep">
+                <h2>{ "Parameter sweep" }</h2>
+                <div class="field">
+                    <label for="sweep_field" title="The varied input">{ "Vary" }</label>
+                    <select id="sweep_field"
+                        onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(s) => s.value()
+                                .and_then(|v| Field::from_id(&v))
+                                .map_or(Msg::Ignore, Msg::SweepField),
+                            _ => Msg::Ignore,
+                        })>
+                        { for Field::ALL.iter().map(|f| html! {
+                            <option value={ f.id() } selected={ *f == self.sweep.field }>{ f.description() }</option>
+                        }) }
+                    </select>
+                </div>
+                { self.sweep_field("from", "From") }
+                { self.sweep_field("to", "To") }
+                { self.sweep_steps("steps", self.sweep.steps) }
+                <div class="field">
+                    <label for="sweep_y" title="The second varied input for a heatmap">{ "and" }</label>
+                    <select id="sweep_y"
+                        onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(s) => Msg::SweepY(s.value().and_then(|v| Field::from_id(&v))),
+                            _ => Msg::Ignore,
+                        })>
+                        <option value="" selected={ self.sweep.y_field.is_none() }>{ "Nothing" }</option>
+                        { for Field::ALL.iter().map(|f| html! {
+                            <option value={ f.id() } selected={ self.sweep.y_field == Some(*f) }>{ f.description() }</option>
+                        }) }
+                    </select>
+                </div>
+                { if self.sweep.y_field.is_some() { self.grid_panel() } else { html! {} } }
+                <div class="outputs">
+                    { for Field::ALL.iter().copied().filter(|f| *f != self.sweep.field).map(|f| {
+                        html! {
+                            <label title={ f.description() }>
+                                <input type="checkbox"
+                                    checked={ self.sweep.outputs.contains(&f) }
+                                    onclick=self.link.callback(move |_| Msg::SweepOutput(f))
+                                    />
+                                { Self::label(f) }
+                            </label>
+                        }
+                    }) }
+                </div>
+                { self.sweep_chart(&self.sweep.results) }
+                { self.sweep_table(&self.sweep.results) }
+            </div>
+        }
+    }
+
+    /// Returns html representing the number of values of a sweep.
+    fn sweep_steps(&self, id: &'static str, steps: usize) -> Html {
+        html! {
+            <div class="field">
+                <label for={ id } title="The number of values">{ "Steps" }</label>
+                <input type="text"
+                    id={ id }
+                    value={ steps.to_string() }
+                    oninput=self.link.callback(move |e: InputData| Msg::Sweep(id, e.value))
+                    />
+            </div>
+        }
+    }
+
+    /// Returns html representing the range of the second varied input and a heatmap of the
+    /// selected field over both inputs.
+    fn grid_panel(&self) -> Html {
+        html! {
+            <>
+                { self.sweep_field("y_from", "From") }
+                { self.sweep_field("y_to", "To") }
+                { self.sweep_steps("y_steps", self.sweep.y_steps) }
+                <div class="field">
+                    <label for="sweep_map" title="The field shown in the heatmap">{ "Map" }</label>
+                    <select id="sweep_map"
+                        onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(s) => s.value()
+                                .and_then(|v| Field::from_id(&v))
+                                .map_or(Msg::Ignore, Msg::SweepMap),
+                            _ => Msg::Ignore,
+                        })>
+                        { for Field::ALL.iter().map(|f| html! {
+                            <option value={ f.id() } selected={ *f == self.sweep.map }>{ f.description() }</option>
+                        }) }
+                    </select>
+                </div>
+                { match &self.sweep.grid {
+                    Some((g, results)) if !results.is_empty() => self.heatmap(g, results),
+                    _ => html! {},
+                } }
+            </>
+        }
+    }
+
+    /// Returns an svg heatmap of the selected field with the first varied input on the horizontal
+    /// and the second one on the vertical axis and a link to download the grid as CSV.
+    fn heatmap(&self, grid: &Grid, results: &[Vec<Calculation>]) -> Html {
+        let field = self.sweep.map;
+        let rows = results.iter().map(|r| sweep::column(r, field)).collect::<Vec<Vec<Option<f64>>>>();
+        let values = rows.iter().flatten().flatten();
+        let min = values.clone().fold(f64::INFINITY, |a, b| a.min(*b));
+        let max = values.fold(f64::NEG_INFINITY, |a, b| a.max(*b));
+        let range = if max > min { max - min } else { 1.0 };
+
+        let width = CHART_WIDTH / grid.x.steps.max(1) as f64;
+        let height = CHART_HEIGHT / grid.y.steps.max(1) as f64;
+        let csv = grid.to_csv(results, field);
+
+        html! {
+            <div class="chart">
+                <svg width={ CHART_WIDTH.to_string() } height={ CHART_HEIGHT.to_string() }>
+                    { for rows.iter().enumerate().map(|(j, row)| html! {
+                        <g>{ for row.iter().enumerate().filter_map(|(i, v)| v.map(|v| (i, v))).map(|(i, v)| html! {
+                            <rect x={ format!("{:.1}", i as f64 * width) }
+                                y={ format!("{:.1}", CHART_HEIGHT - (j + 1) as f64 * height) }
+                                width={ format!("{:.1}", width + 0.5) }
+                                height={ format!("{:.1}", height + 0.5) }
+                                fill={ heat_color((v - min) / range) }>
+                                <title>{ field.display(Num::Out(v), 4) }</title>
+                            </rect>
+                        }) }</g>
+                    }) }
+                </svg>
+                <ul class="legend">
+                    <li>{ format!("→ {} {} … {}", grid.x.field.id(), grid.x.from, grid.x.to) }</li>
+                    <li>{ format!("↑ {} {} … {}", grid.y.field.id(), grid.y.from, grid.y.to) }</li>
+                    <li>{ format!("{} {} … {}", field.id(), field.display(Num::Out(min), 4), field.display(Num::Out(max), 4)) }</li>
+                </ul>
+                <a href={ format!("data:text/csv;charset=utf-8,{}", encode_uri(&csv)) }
+                    download={ format!("{}.csv", field.id()) }>{ "Export CSV" }</a>
+            </div>
+        }
+    }
+
+    /// Returns the grid of both varied inputs or None if a range is incomplete. The first input
+    /// is limited to as many values as the second one.
+    fn sweep_grid(&self) -> Option<Grid> {
+        let x = Sweep::new(self.sweep.field, self.sweep.from.as_option()?, self.sweep.to.as_option()?, self.sweep.steps.min(MAX_GRID_STEPS));
+        let y = Sweep::new(self.sweep.y_field?, self.sweep.y_from.as_option()?, self.sweep.y_to.as_option()?, self.sweep.y_steps);
+
+        Some(Grid::new(x, y))
+    }
+
+    /// Returns html representing an input field of the sweep range.
+    fn sweep_field(&self, id: &'static str, label: &str) -> Html {
+        html! {
+            <div class="field">
+                <label for={ id }>{ label }</label>
+                <input type="text"
+                    id={ id }
+                    oninput=self.link.callback(move |e: InputData| Msg::Sweep(id, e.value))
+                    />
+            </div>
+        }
+    }
+
+    /// Returns html representing a table of the varied input and the selected outputs.
+    fn sweep_table(&self, results: &[Calculation]) -> Html {
+        if results.is_empty() {
+            return html! {};
+        }
+
+        let fields = std::iter::once(self.sweep.field)
+            .chain(self.sweep.outputs.iter().copied())
+            .collect::<Vec<Field>>();
+
+        html! {
+            <table>
+                <tr>
+                    { for fields.iter().map(|f| html! {
+                        <th title={ f.description() }>{ Self::label(*f) }</th>
+                    }) }
+                </tr>
+                { for results.iter().map(|c| html! {
+                    <tr>
+                        { for fields.iter().map(|f| html! {
+                            <td>{ f.display(c.get(*f), self.significant_figures) }</td>
+                        }) }
+                    </tr>
+                }) }
+            </table>
+        }
+    }
+
+    /// Returns an svg line chart of the selected outputs over the varied input. Every line is
+    /// scaled to its own range since the outputs have different units.
+    fn sweep_chart(&self, results: &[Calculation]) -> Html {
+        if results.len() < 2 || self.sweep.outputs.is_empty() {
+            return html! {};
+        }
+
+        let step = CHART_WIDTH / (results.len() - 1) as f64;
+        let line = |values: &[Option<f64>]| {
+            let present = values.iter().flatten();
+            let min = present.clone().fold(f64::INFINITY, |a, b| a.min(*b));
+            let max = present.fold(f64::NEG_INFINITY, |a, b| a.max(*b));
+            let range = if max > min { max - min } else { 1.0 };
+
+            let points = values.iter()
+                .enumerate()
+                .filter_map(|(i, v)| v.map(|v| format!("{:.1},{:.1}", i as f64 * step, CHART_HEIGHT - (v - min) / range * CHART_HEIGHT)))
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            (points, min, max)
+        };
+        let lines = self.sweep.outputs.iter()
+            .map(|f| line(&sweep::column(results, *f)))
+            .collect::<Vec<(String, f64, f64)>>();
+
+        html! {
+            <div class="chart">
+                <svg width={ CHART_WIDTH.to_string() } height={ CHART_HEIGHT.to_string() }>
+                    { for lines.iter().enumerate().map(|(i, (points, _, _))| html! {
+                        <polyline points={ points } fill="none" stroke={ CHART_COLORS[i % CHART_COLORS.len()] } stroke-width="2" />
+                    }) }
+                </svg>
+                <ul class="legend">
+                    { for self.sweep.outputs.iter().zip(&lines).enumerate().map(|(i, (f, (_, min, max)))| html! {
+                        <li style={ format!("color: {}", CHART_COLORS[i % CHART_COLORS.len()]) }>
+                            { format!("{} {} … {}", f.id(), f.display(Num::Out(*min), 4), f.display(Num::Out(*max), 4)) }
+                        </li>
+                    }) }
+                </ul>
+            </div>
+        }
+    }
+
+    /// Returns the solved calculations of the sweep or nothing if the range is incomplete.
+    fn sweep_results(&self) -> Vec<Calculation> {
+        match (self.sweep.from.as_option(), self.sweep.to.as_option()) {
+            (Some(from), Some(to)) => Sweep::new(self.sweep.field, from, to, self.sweep.steps)
+                .run(&self.calc),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the transmission ratio that is optimal for the motor and the load of the calculation.
+    /// If available ratios were entered the best of them is returned.
+    pub fn suggested_ratio(&self) -> Option<f64> {
+        let input = |n: Num| if n.is_input() { n.as_option() } else { None };
+
+        let motor = Motor {
+            name: String::new(),
+            u: input(self.calc[Field::Voltage]).unwrap_or(f64::INFINITY),
+            r_a: self.calc[Field::ArmatureResistance].as_option().unwrap_or(0.0),
+            k_m: self.ratio.k_m.as_option()?,
+            i_0: self.ratio.i_0.as_option().unwrap_or(0.0),
+            j: self.ratio.j_m.as_option().unwrap_or(0.0),
+            m_max: f64::INFINITY,
+            n_max: f64::INFINITY,
+            mass: 0.0,
+            cost: 0.0,
+            length: 0.0,
+            diameter: 0.0,
+        };
+        let load = Load {
+            m_t: input(self.calc[Field::TransmissionTorque])?,
+            n_t: input(self.calc[Field::TransmissionSpeed])?,
+            j_t: self.ratio.j_t.as_option().unwrap_or(0.0),
+        };
+        let eta_t = input(self.calc[Field::TransmissionEfficiency]).unwrap_or(100.0);
+
+        if self.ratio.ratios.is_empty() {
+            optimal_ratio(&motor, &load, eta_t, self.ratio.objective)
+        } else {
+            best_available_ratio(&motor, &load, eta_t, self.ratio.objective, &self.ratio.ratios)
+        }
+    }
+}
+
+/// Returns the color of a heatmap cell for a value between 0 and 1, from blue for low to red for
+/// high values.
+fn heat_color(t: f64) -> String {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |a: f64, b: f64| (a + (b - a) * t).round() as u8;
+
+    format!("rgb({}, {}, {})", channel(43.0, 200.0), channel(58.0, 87.0), channel(103.0, 66.0))
+}
+
+/// Percent encodes the characters of the string that aren't allowed in a data url.
+fn encode_uri(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' | ',' => c.to_string(),
+            c => c.to_string().bytes().map(|b| format!("%{:02X}", b)).collect(),
+        })
+        .collect()
+}